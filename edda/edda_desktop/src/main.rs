@@ -5,12 +5,29 @@ use mcp_client::{McpClient, Tool};
 use std::sync::Arc;
 use tracing_subscriber::{fmt, EnvFilter};
 
+/// storage key the last-used remote endpoint is persisted under between runs
+const REMOTE_ENDPOINT_KEY: &str = "remote_endpoint";
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+struct RemoteEndpoint {
+    url: String,
+    token: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConnectionMode {
+    LocalBinary,
+    Remote,
+}
+
 struct EddaDesktopApp {
     runtime: tokio::runtime::Runtime,
     client: Option<Arc<McpClient>>,
     tools: Vec<Tool>,
     status: String,
     error_message: Option<String>,
+    connection_mode: ConnectionMode,
+    remote_endpoint: RemoteEndpoint,
 }
 
 impl Default for EddaDesktopApp {
@@ -21,13 +38,23 @@ impl Default for EddaDesktopApp {
             tools: Vec::new(),
             status: "Not connected".to_string(),
             error_message: None,
+            connection_mode: ConnectionMode::LocalBinary,
+            remote_endpoint: RemoteEndpoint::default(),
         }
     }
 }
 
 impl EddaDesktopApp {
-    fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
         let mut app = Self::default();
+        if let Some(endpoint) =
+            cc.storage.and_then(|storage| eframe::get_value::<RemoteEndpoint>(storage, REMOTE_ENDPOINT_KEY))
+        {
+            if !endpoint.url.is_empty() {
+                app.connection_mode = ConnectionMode::Remote;
+            }
+            app.remote_endpoint = endpoint;
+        }
         app.connect_to_mcp();
         app
     }
@@ -36,10 +63,15 @@ impl EddaDesktopApp {
         self.status = "Connecting...".to_string();
         self.error_message = None;
 
+        let mode = self.connection_mode;
         let binary_path = self.get_binary_path();
+        let endpoint = self.remote_endpoint.clone();
 
         match self.runtime.block_on(async {
-            let client = McpClient::spawn(&binary_path).await?;
+            let client = match mode {
+                ConnectionMode::LocalBinary => McpClient::spawn(&binary_path).await?,
+                ConnectionMode::Remote => McpClient::connect(&endpoint.url, &endpoint.token).await?,
+            };
             let tools = client.list_tools().await?;
             Ok::<_, anyhow::Error>((client, tools))
         }) {
@@ -77,6 +109,26 @@ impl eframe::App for EddaDesktopApp {
 
             ui.separator();
 
+            // server picker
+            ui.horizontal(|ui| {
+                ui.label("Server:");
+                ui.selectable_value(&mut self.connection_mode, ConnectionMode::LocalBinary, "Local binary");
+                ui.selectable_value(&mut self.connection_mode, ConnectionMode::Remote, "Remote URL + token");
+            });
+
+            if self.connection_mode == ConnectionMode::Remote {
+                ui.horizontal(|ui| {
+                    ui.label("URL:");
+                    ui.text_edit_singleline(&mut self.remote_endpoint.url);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Token:");
+                    ui.add(egui::TextEdit::singleline(&mut self.remote_endpoint.token).password(true));
+                });
+            }
+
+            ui.separator();
+
             // status bar
             ui.horizontal(|ui| {
                 ui.label("Status:");
@@ -123,6 +175,12 @@ impl eframe::App for EddaDesktopApp {
         });
     }
 
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        if self.connection_mode == ConnectionMode::Remote {
+            eframe::set_value(storage, REMOTE_ENDPOINT_KEY, &self.remote_endpoint);
+        }
+    }
+
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
         // client will be dropped and cleaned up automatically
         self.client = None;
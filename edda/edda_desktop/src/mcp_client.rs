@@ -0,0 +1,188 @@
+//! A minimal MCP (Model Context Protocol) client: JSON-RPC 2.0 requests, one per line, sent
+//! either to a locally spawned `edda_mcp` process over stdio or to a remote server over HTTP.
+//! [`McpClient::list_tools`] (and any future request) works the same way regardless of which
+//! transport `McpClient` was constructed with.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Tool {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+}
+
+#[derive(Serialize)]
+struct JsonRpcRequest<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcResponse {
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+enum Transport {
+    /// a locally spawned `edda_mcp` process, speaking newline-delimited JSON-RPC over its stdio
+    Stdio {
+        // kept alive only so the process isn't torn down; never read directly
+        _child: Child,
+        stdin: Mutex<ChildStdin>,
+        stdout: Mutex<BufReader<ChildStdout>>,
+    },
+    /// an MCP server reachable over HTTP, presenting `token` as a bearer credential on every
+    /// request — mirrors how editor CLIs hand a session token to a remote dev-server they manage
+    Remote {
+        http: reqwest::Client,
+        url: String,
+        token: String,
+    },
+}
+
+pub struct McpClient {
+    transport: Transport,
+    next_id: AtomicU64,
+}
+
+impl McpClient {
+    /// launch `binary_path` and speak MCP over its stdio
+    pub async fn spawn(binary_path: &str) -> Result<Self> {
+        let mut child = tokio::process::Command::new(binary_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("failed to launch MCP server at '{binary_path}'"))?;
+
+        let stdin = child.stdin.take().context("child stdin was not piped")?;
+        let stdout = child.stdout.take().context("child stdout was not piped")?;
+
+        let client = Self {
+            transport: Transport::Stdio {
+                _child: child,
+                stdin: Mutex::new(stdin),
+                stdout: Mutex::new(BufReader::new(stdout)),
+            },
+            next_id: AtomicU64::new(1),
+        };
+        client.initialize().await?;
+        Ok(client)
+    }
+
+    /// attach to an MCP server already running at `url`, authenticating with a bearer `token`
+    /// instead of spawning a local process
+    pub async fn connect(url: &str, token: &str) -> Result<Self> {
+        let client = Self {
+            transport: Transport::Remote {
+                http: reqwest::Client::new(),
+                url: url.to_string(),
+                token: token.to_string(),
+            },
+            next_id: AtomicU64::new(1),
+        };
+        client.initialize().await?;
+        Ok(client)
+    }
+
+    async fn initialize(&self) -> Result<()> {
+        self.call(
+            "initialize",
+            Some(serde_json::json!({
+                "protocolVersion": "2024-11-05",
+                "clientInfo": { "name": "edda-desktop", "version": env!("CARGO_PKG_VERSION") },
+                "capabilities": {},
+            })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list_tools(&self) -> Result<Vec<Tool>> {
+        #[derive(Deserialize)]
+        struct ToolsList {
+            tools: Vec<Tool>,
+        }
+        let result = self.call("tools/list", None).await?;
+        let parsed: ToolsList = serde_json::from_value(result).context("malformed tools/list response")?;
+        Ok(parsed.tools)
+    }
+
+    async fn call(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let request = JsonRpcRequest { jsonrpc: "2.0", id, method, params };
+
+        let response = match &self.transport {
+            Transport::Stdio { stdin, stdout, .. } => self.call_stdio(stdin, stdout, &request).await?,
+            Transport::Remote { http, url, token } => self.call_remote(http, url, token, &request).await?,
+        };
+
+        if let Some(error) = response.error {
+            bail!("MCP server returned error {}: {}", error.code, error.message);
+        }
+        response.result.context("MCP response had neither a result nor an error")
+    }
+
+    async fn call_stdio(
+        &self,
+        stdin: &Mutex<ChildStdin>,
+        stdout: &Mutex<BufReader<ChildStdout>>,
+        request: &JsonRpcRequest<'_>,
+    ) -> Result<JsonRpcResponse> {
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+
+        let mut stdin = stdin.lock().await;
+        stdin.write_all(line.as_bytes()).await?;
+        stdin.flush().await?;
+        drop(stdin);
+
+        let mut stdout = stdout.lock().await;
+        let mut buf = String::new();
+        stdout.read_line(&mut buf).await?;
+        if buf.is_empty() {
+            bail!("MCP server closed stdout");
+        }
+        serde_json::from_str(&buf).context("malformed JSON-RPC response from MCP server")
+    }
+
+    async fn call_remote(
+        &self,
+        http: &reqwest::Client,
+        url: &str,
+        token: &str,
+        request: &JsonRpcRequest<'_>,
+    ) -> Result<JsonRpcResponse> {
+        let response = http
+            .post(url)
+            .bearer_auth(token)
+            .json(request)
+            .send()
+            .await
+            .context("failed to reach remote MCP server")?
+            .error_for_status()
+            .context("remote MCP server returned an error status")?;
+        response.json().await.context("malformed JSON-RPC response from remote MCP server")
+    }
+}
@@ -1,3 +1,4 @@
+use edda_mcp::env::EnvVars;
 use eyre::{Result, bail};
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -25,27 +26,31 @@ impl Default for AgentConfig {
     }
 }
 
+/// parse a "backend" or "backend:model" spec, e.g. "claude", "opencode:opencode/kimi-k2.5-free"
+pub fn parse_agent_spec(s: &str) -> std::result::Result<AgentConfig, String> {
+    let (backend_str, model) = match s.split_once(':') {
+        Some((b, m)) => (b, Some(m.to_string())),
+        None => (s, None),
+    };
+    let backend = match backend_str {
+        "claude" => AgentBackend::Claude,
+        "opencode" => AgentBackend::OpenCode,
+        other => {
+            return Err(format!(
+                "unknown agent backend: '{other}' (expected 'claude' or 'opencode')"
+            ));
+        }
+    };
+    Ok(AgentConfig { backend, model })
+}
+
 impl<'de> serde::Deserialize<'de> for AgentConfig {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        // "backend" or "backend:model" — e.g. "claude", "opencode:opencode/kimi-k2.5-free"
-        let (backend_str, model) = match s.split_once(':') {
-            Some((b, m)) => (b, Some(m.to_string())),
-            None => (s.as_str(), None),
-        };
-        let backend = match backend_str {
-            "claude" => AgentBackend::Claude,
-            "opencode" => AgentBackend::OpenCode,
-            other => {
-                return Err(serde::de::Error::custom(format!(
-                    "unknown agent backend: '{other}' (expected 'claude' or 'opencode')"
-                )));
-            }
-        };
-        Ok(AgentConfig { backend, model })
+        parse_agent_spec(&s).map_err(serde::de::Error::custom)
     }
 }
 
@@ -61,6 +66,11 @@ pub struct ForgeConfig {
     /// extra host paths to expose to runtime
     #[serde(default)]
     pub mounts: Vec<MountConfig>,
+    #[serde(default)]
+    pub artifacts: ArtifactsConfig,
+    /// where to push a branch and open a pull request when `--publish-pr` is passed
+    #[serde(default)]
+    pub forge: Option<PublishConfig>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -74,10 +84,45 @@ pub struct MountConfig {
     pub local_target: Option<String>,
 }
 
+/// glob patterns, relative to `project.workdir`, pulled out of the sandbox into `artifacts/`
+/// next to `--output` once `State::Done` is reached (compiled binaries, coverage reports,
+/// generated docs, benchmark JSON, ...), regardless of whether the run produced a patch or an
+/// exported directory.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ArtifactsConfig {
+    #[serde(default, deserialize_with = "deserialize_string_list")]
+    pub patterns: Vec<String>,
+}
+
+/// a `[forge]` block: which git forge to push a generated branch to and open a PR against
+#[derive(Debug, Clone, Deserialize)]
+pub struct PublishConfig {
+    pub kind: ForgeKind,
+    /// `https://gitea.example.com` (Gitea/Forgejo) or `https://github.com`/an Enterprise host
+    pub base_url: String,
+    /// `owner/repo`
+    pub repo: String,
+    /// name of the host env var holding an API token, resolved the same way as `container.secrets`
+    pub token_env: String,
+    #[serde(default = "default_pr_base_branch")]
+    pub base_branch: String,
+}
+
+fn default_pr_base_branch() -> String {
+    "main".to_string()
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    Gitea,
+    GitHub,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct PatchConfig {
     /// glob patterns to exclude from the output patch (git pathspec exclude)
-    #[serde(default = "default_patch_excludes")]
+    #[serde(default = "default_patch_excludes", deserialize_with = "deserialize_string_list")]
     pub exclude: Vec<String>,
 }
 
@@ -103,14 +148,143 @@ fn default_patch_excludes() -> Vec<String> {
     ]
 }
 
+/// accepts either a bare string or a TOML array wherever a list of strings is natural,
+/// e.g. `setup = "apt-get update"` or `setup = ["apt-get update", "apt-get install -y git"]`.
+/// a bare string is split on whitespace into entries; modeled on cargo config's `StringList`.
+#[derive(Debug, Clone, Default)]
+struct StringList(Vec<String>);
+
+impl<'de> Deserialize<'de> for StringList {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            One(String),
+            Many(Vec<String>),
+        }
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::One(s) => StringList(s.split_whitespace().map(str::to_string).collect()),
+            Repr::Many(v) => StringList(v),
+        })
+    }
+}
+
+fn deserialize_string_list<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(StringList::deserialize(deserializer)?.0)
+}
+
+/// accepts either a bare shell command string or an array of lines, joined with newlines
+/// so a multi-line setup-then-test step doesn't require chaining with `&&`.
+fn deserialize_command<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match Repr::deserialize(deserializer)? {
+        Repr::One(s) => s,
+        Repr::Many(v) => v.join("\n"),
+    })
+}
+
+/// accepts either a single whole shell command or an array of whole commands. Unlike
+/// `deserialize_string_list`, a bare string is never split on whitespace — each entry here is a
+/// full command line passed to `sh -c`, not a word/glob, so `setup = "apt-get update"` must stay
+/// one command rather than becoming `["apt-get", "update"]`.
+fn deserialize_command_list<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match Repr::deserialize(deserializer)? {
+        Repr::One(s) => vec![s],
+        Repr::Many(v) => v,
+    })
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ContainerConfig {
     pub image: String,
+    #[serde(deserialize_with = "deserialize_command_list")]
     pub setup: Vec<String>,
     pub user: String,
     pub user_setup: String,
     #[serde(default)]
     pub env: HashMap<String, String>,
+    /// host-side secret names (resolved from `~/.edda/.env`/system env at launch) to inject
+    /// into the container's runtime environment; never written into the config or the patch
+    #[serde(default)]
+    pub secrets: Vec<String>,
+}
+
+/// prefix marking a `${secret:NAME}` reference inside a `container.env` value
+const SECRET_MARKER_PREFIX: &str = "${secret:";
+
+impl ContainerConfig {
+    /// names referenced either via `secrets = [...]` or a `${secret:NAME}` marker in `env`
+    fn referenced_secret_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.secrets.iter().map(String::as_str).collect();
+        for value in self.env.values() {
+            if let Some(name) = parse_secret_marker(value) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// resolve `secrets`/`${secret:NAME}` references against `env` into a fresh map suitable
+    /// for injecting into the container's runtime environment. The returned values must never
+    /// be written into the generated config, the output patch, or a non-redacted log line.
+    pub fn resolve_secrets(&self, env: &EnvVars) -> Result<HashMap<String, String>> {
+        let mut resolved = HashMap::new();
+        for name in &self.secrets {
+            let value = env.get(name).ok_or_else(|| missing_secret_error(name))?;
+            resolved.insert(name.clone(), value.to_string());
+        }
+        for (key, value) in &self.env {
+            if let Some(name) = parse_secret_marker(value) {
+                let value = env.get(name).ok_or_else(|| missing_secret_error(name))?;
+                // the env key holding the marker gets the secret's value, e.g.
+                // env.API_KEY = "${secret:ANTHROPIC_API_KEY}" -> container env API_KEY=<value>
+                resolved.insert(key.clone(), value.to_string());
+            }
+        }
+        Ok(resolved)
+    }
+}
+
+fn parse_secret_marker(value: &str) -> Option<&str> {
+    value
+        .strip_prefix(SECRET_MARKER_PREFIX)
+        .and_then(|rest| rest.strip_suffix('}'))
+}
+
+/// whether a `container.env` value is a `${secret:NAME}` marker rather than a literal
+pub(crate) fn is_secret_marker(value: &str) -> bool {
+    parse_secret_marker(value).is_some()
+}
+
+fn missing_secret_error(name: &str) -> eyre::Report {
+    eyre::eyre!(
+        "secret '{name}' is referenced by container.secrets/env but not set. \
+         Please add it to ~/.edda/.env or system environment.\n\
+         See ~/.edda/.env.example for template."
+    )
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -118,9 +292,42 @@ pub struct ProjectConfig {
     pub language: String,
     pub source: String,
     pub workdir: String,
-    /// glob patterns to exclude when mounting source into container
-    #[serde(default)]
+    /// glob patterns to exclude when mounting source into container; always applied, on top of
+    /// `respect_gitignore` if that's also on
+    #[serde(default, deserialize_with = "deserialize_string_list")]
     pub exclude: Vec<String>,
+    /// also honor nested `.gitignore`/`.ignore` files in the source tree when mounting it into
+    /// the sandbox, layered on top of `exclude`
+    #[serde(default = "default_respect_gitignore")]
+    pub respect_gitignore: bool,
+    /// Linux namespace/seccomp confinement for commands the local runtime (`--runtime local`)
+    /// executes; the dagger runtime already runs inside a container, so this has no effect there
+    #[serde(default)]
+    pub isolation: IsolationConfig,
+}
+
+fn default_respect_gitignore() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IsolationConfig {
+    /// opt in to running commands inside fresh user/mount/pid (and, unless `network` is set,
+    /// network) namespaces with a seccomp filter. Best-effort: falls back to the unconfined
+    /// `sh -c` exec on kernels or privilege levels that don't support it.
+    #[serde(default)]
+    pub enabled: bool,
+    /// allow network access inside the isolated namespace; false gives it a private namespace
+    /// with only loopback and seccomp-denies raw `AF_PACKET` socket creation
+    #[serde(default = "default_isolation_network")]
+    pub network: bool,
+    /// extra host paths bind-mounted read-only into the isolated root, at the same absolute path
+    #[serde(default, deserialize_with = "deserialize_string_list")]
+    pub readonly_paths: Vec<String>,
+}
+
+fn default_isolation_network() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -131,19 +338,450 @@ pub struct StepsConfig {
 #[derive(Debug, Clone, Deserialize)]
 pub struct ValidateStep {
     pub name: String,
+    #[serde(deserialize_with = "deserialize_command")]
     pub command: String,
+    /// cancel the step and fail it with a timeout error if it runs longer than this
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// run this step concurrently with its neighbouring `parallel` steps instead of waiting for
+    /// them to finish first. A run of consecutive `parallel = true` steps forms one batch; a
+    /// `parallel = false` step always runs alone, after every earlier batch has finished.
+    #[serde(default)]
+    pub parallel: bool,
+    /// retry this step up to this many additional times if it fails before giving up; a step
+    /// that fails and later passes within its retry budget is reported as flaky rather than a
+    /// hard failure
+    #[serde(default)]
+    pub retries: u32,
+}
+
+/// accepts `extends = "../base.toml"` or `extends = ["../a.toml", "../b.toml"]`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum ExtendsField {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl ExtendsField {
+    fn into_paths(self) -> Vec<String> {
+        match self {
+            ExtendsField::One(p) => vec![p],
+            ExtendsField::Many(ps) => ps,
+        }
+    }
+}
+
+/// how a child's `steps.validate`/`mounts` combine with the inherited base(s)
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum ExtendMergeMode {
+    #[default]
+    Append,
+    Replace,
+}
+
+/// a single `forge.toml` layer, with every field optional so a child config only needs
+/// to specify what it overrides from its `extends` base(s)
+#[derive(Debug, Clone, Deserialize, Default)]
+struct ForgeConfigLayer {
+    #[serde(default)]
+    extends: Option<ExtendsField>,
+    #[serde(default)]
+    merge: ExtendMergeMode,
+    #[serde(default)]
+    agent: Option<AgentConfig>,
+    #[serde(default)]
+    container: Option<ContainerLayer>,
+    #[serde(default)]
+    project: Option<ProjectLayer>,
+    #[serde(default)]
+    patch: Option<PatchConfig>,
+    #[serde(default)]
+    steps: Option<StepsLayer>,
+    #[serde(default)]
+    mounts: Vec<MountConfig>,
+    #[serde(default)]
+    artifacts: Option<ArtifactsConfig>,
+    #[serde(default)]
+    forge: Option<PublishConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+struct ContainerLayer {
+    image: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_command_list")]
+    setup: Vec<String>,
+    user: Option<String>,
+    user_setup: Option<String>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+    #[serde(default)]
+    secrets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+struct ProjectLayer {
+    language: Option<String>,
+    source: Option<String>,
+    workdir: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_list")]
+    exclude: Vec<String>,
+    #[serde(default)]
+    respect_gitignore: Option<bool>,
+    #[serde(default)]
+    isolation: Option<IsolationConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+struct StepsLayer {
+    #[serde(default)]
+    validate: Vec<ValidateStep>,
+}
+
+/// right-biased merge: fields set on `other` win, empty/default fields fall through to `self`.
+pub trait Merge {
+    fn merge(&mut self, other: Self);
+}
+
+impl Merge for String {
+    fn merge(&mut self, other: Self) {
+        if !other.is_empty() {
+            *self = other;
+        }
+    }
+}
+
+impl<T> Merge for Vec<T> {
+    fn merge(&mut self, mut other: Self) {
+        self.append(&mut other);
+    }
+}
+
+impl<V> Merge for HashMap<String, V> {
+    fn merge(&mut self, other: Self) {
+        self.extend(other);
+    }
+}
+
+impl Merge for ContainerConfig {
+    fn merge(&mut self, other: Self) {
+        self.image.merge(other.image);
+        self.setup.merge(other.setup);
+        self.user.merge(other.user);
+        self.user_setup.merge(other.user_setup);
+        self.env.merge(other.env);
+        self.secrets.merge(other.secrets);
+    }
+}
+
+impl Merge for ProjectConfig {
+    fn merge(&mut self, other: Self) {
+        self.language.merge(other.language);
+        self.source.merge(other.source);
+        self.workdir.merge(other.workdir);
+        self.exclude.merge(other.exclude);
+        self.respect_gitignore = other.respect_gitignore;
+        self.isolation.merge(other.isolation);
+    }
+}
+
+impl Merge for IsolationConfig {
+    fn merge(&mut self, other: Self) {
+        self.enabled = other.enabled;
+        self.network = other.network;
+        self.readonly_paths.merge(other.readonly_paths);
+    }
+}
+
+impl Merge for StepsConfig {
+    fn merge(&mut self, other: Self) {
+        self.validate.merge(other.validate);
+    }
+}
+
+impl Merge for PatchConfig {
+    fn merge(&mut self, other: Self) {
+        self.exclude.merge(other.exclude);
+    }
+}
+
+impl Merge for ArtifactsConfig {
+    fn merge(&mut self, other: Self) {
+        self.patterns.merge(other.patterns);
+    }
+}
+
+impl Merge for ForgeConfig {
+    fn merge(&mut self, other: Self) {
+        self.container.merge(other.container);
+        self.project.merge(other.project);
+        self.steps.merge(other.steps);
+        self.patch.merge(other.patch);
+        self.mounts.merge(other.mounts);
+        self.artifacts.merge(other.artifacts);
+    }
+}
+
+/// CLI-sourced overrides applied after `ForgeConfig::load` and before `validate()`.
+///
+/// Every field is optional/empty by default so an unset flag is a no-op. Scalars
+/// (`agent`, `image`, `workdir`) replace the loaded value; collections (`env`, `exclude`)
+/// append via [`Merge`].
+#[derive(Debug, Clone, Default)]
+pub struct ForgeOverride {
+    pub agent: Option<AgentConfig>,
+    pub image: Option<String>,
+    pub workdir: Option<String>,
+    pub env: HashMap<String, String>,
+    pub exclude: Vec<String>,
+}
+
+impl ForgeOverride {
+    pub fn is_empty(&self) -> bool {
+        self.agent.is_none()
+            && self.image.is_none()
+            && self.workdir.is_none()
+            && self.env.is_empty()
+            && self.exclude.is_empty()
+    }
+
+    /// apply overrides on top of a loaded config (before `validate()`)
+    pub fn apply_to(self, config: &mut ForgeConfig) {
+        if let Some(agent) = self.agent {
+            config.agent = agent;
+        }
+        config.container.image.merge(self.image.unwrap_or_default());
+        config.project.workdir.merge(self.workdir.unwrap_or_default());
+        config.container.env.merge(self.env);
+        config.project.exclude.merge(self.exclude);
+    }
 }
 
 impl ForgeConfig {
     pub fn load(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| eyre::eyre!("failed to read config {}: {e}", path.display()))?;
-        let config: ForgeConfig = toml::from_str(&content)
-            .map_err(|e| eyre::eyre!("failed to parse config {}: {e}", path.display()))?;
-        config.validate()?;
+        Self::load_with_override(path, ForgeOverride::default())
+    }
+
+    /// load, resolve `${VAR}`/`FORGE_*` env layering, then apply CLI overrides, then validate.
+    ///
+    /// Precedence (highest wins): `FORGE_*` env override > `${VAR}` interpolation > literal TOML.
+    /// CLI overrides (`over`) apply on top of all of the above, so an overridden field
+    /// (e.g. a `--workdir` that fills in an otherwise-missing value) still passes `validate()`.
+    pub fn load_with_override(path: &Path, over: ForgeOverride) -> Result<Self> {
+        let mut config = Self::parse(path)?;
+        let env = EnvVars::load()?;
+        config.interpolate(&env)?;
+        config.apply_env_overrides(&env)?;
+        over.apply_to(&mut config);
+        config.validate(&env)?;
         Ok(config)
     }
 
+    /// expand `${VAR}`/`${VAR:-default}` references in string fields using `env`
+    fn interpolate(&mut self, env: &EnvVars) -> Result<()> {
+        self.container.image = interpolate_str(&self.container.image, env)?;
+        for cmd in &mut self.container.setup {
+            *cmd = interpolate_str(cmd, env)?;
+        }
+        self.project.workdir = interpolate_str(&self.project.workdir, env)?;
+        for mount in &mut self.mounts {
+            mount.host = interpolate_str(&mount.host, env)?;
+        }
+        for step in &mut self.steps.validate {
+            step.command = interpolate_str(&step.command, env)?;
+        }
+        Ok(())
+    }
+
+    /// apply `FORGE_`-prefixed overrides following cargo's key-mapping convention
+    /// (`container.image` -> `FORGE_CONTAINER_IMAGE`)
+    fn apply_env_overrides(&mut self, env: &EnvVars) -> Result<()> {
+        if let Some(v) = env.get("FORGE_AGENT") {
+            self.agent = parse_agent_spec(v).map_err(|e| eyre::eyre!("FORGE_AGENT: {e}"))?;
+        }
+        if let Some(v) = env.get("FORGE_CONTAINER_IMAGE") {
+            self.container.image = v.to_string();
+        }
+        if let Some(v) = env.get("FORGE_CONTAINER_USER") {
+            self.container.user = v.to_string();
+        }
+        if let Some(v) = env.get("FORGE_CONTAINER_USER_SETUP") {
+            self.container.user_setup = v.to_string();
+        }
+        if let Some(v) = env.get("FORGE_PROJECT_LANGUAGE") {
+            self.project.language = v.to_string();
+        }
+        if let Some(v) = env.get("FORGE_PROJECT_SOURCE") {
+            self.project.source = v.to_string();
+        }
+        if let Some(v) = env.get("FORGE_PROJECT_WORKDIR") {
+            self.project.workdir = v.to_string();
+        }
+        Ok(())
+    }
+
+    /// parse (resolving any `extends` chain) without validating, used when CLI overrides
+    /// must apply before validation
+    pub(crate) fn parse(path: &Path) -> Result<Self> {
+        let mut visited = std::collections::HashSet::new();
+        let mut config = Self::load_resolved(path, &mut visited)?;
+        // no layer in the chain listed any patch excludes: fall back to the defaults
+        if config.patch.exclude.is_empty() {
+            config.patch = PatchConfig::default();
+        }
+        Ok(config)
+    }
+
+    /// an all-empty config, used as the accumulator base while resolving an `extends` chain
+    fn empty() -> Self {
+        Self {
+            agent: AgentConfig::default(),
+            container: ContainerConfig {
+                image: String::new(),
+                setup: vec![],
+                user: String::new(),
+                user_setup: String::new(),
+                env: HashMap::new(),
+                secrets: vec![],
+            },
+            project: ProjectConfig {
+                language: String::new(),
+                source: String::new(),
+                workdir: String::new(),
+                exclude: vec![],
+                respect_gitignore: default_respect_gitignore(),
+                isolation: IsolationConfig::default(),
+            },
+            steps: StepsConfig { validate: vec![] },
+            patch: PatchConfig { exclude: vec![] },
+            mounts: vec![],
+            artifacts: ArtifactsConfig::default(),
+            forge: None,
+        }
+    }
+
+    /// recursively resolve `extends = "path"` / `extends = ["a", "b"]`, merging base(s) before
+    /// the current layer. Base paths are resolved relative to the including file's directory.
+    ///
+    /// Cycle detection uses `visited` as the set of canonicalized paths on the current
+    /// resolution *path* (stack semantics: inserted on entry, removed before returning), not
+    /// every path ever seen across the whole tree. Two independent branches extending the same
+    /// shared base (a diamond: `app.toml` extends both `common.toml` and `ci.toml`, which both
+    /// extend `base.toml`) is legitimate layering, not a cycle — `base.toml` must be revisitable
+    /// once the branch that first resolved it has returned.
+    fn load_resolved(path: &Path, visited: &mut std::collections::HashSet<PathBuf>) -> Result<Self> {
+        let canonical = std::fs::canonicalize(path)
+            .map_err(|e| eyre::eyre!("failed to resolve config path {}: {e}", path.display()))?;
+        if !visited.insert(canonical.clone()) {
+            bail!(
+                "config inheritance cycle detected while resolving 'extends' at {}",
+                path.display()
+            );
+        }
+
+        let result = (|| {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| eyre::eyre!("failed to read config {}: {e}", path.display()))?;
+            let layer: ForgeConfigLayer = toml::from_str(&content)
+                .map_err(|e| eyre::eyre!("failed to parse config {}: {e}", path.display()))?;
+
+            let dir = path.parent().unwrap_or_else(|| Path::new("."));
+            let mode = layer.merge;
+
+            let mut config = match &layer.extends {
+                None => Self::empty(),
+                Some(extends) => {
+                    let mut acc = Self::empty();
+                    for base_rel in extends.clone().into_paths() {
+                        let base_path = dir.join(&base_rel);
+                        let base = Self::load_resolved(&base_path, visited)?;
+                        acc.agent = base.agent.clone();
+                        acc.forge = base.forge.clone();
+                        acc.merge(base);
+                    }
+                    acc
+                }
+            };
+
+            config.apply_layer(layer, mode);
+            Ok(config)
+        })();
+
+        visited.remove(&canonical);
+        result
+    }
+
+    /// apply a single resolved layer's overrides on top of `self` (the merged base state)
+    fn apply_layer(&mut self, layer: ForgeConfigLayer, mode: ExtendMergeMode) {
+        if let Some(agent) = layer.agent {
+            self.agent = agent;
+        }
+        if let Some(forge) = layer.forge {
+            self.forge = Some(forge);
+        }
+        if let Some(c) = layer.container {
+            if let Some(image) = c.image {
+                self.container.image = image;
+            }
+            self.container.setup.extend(c.setup);
+            if let Some(user) = c.user {
+                self.container.user = user;
+            }
+            if let Some(user_setup) = c.user_setup {
+                self.container.user_setup = user_setup;
+            }
+            self.container.env.extend(c.env);
+            self.container.secrets.extend(c.secrets);
+        }
+        if let Some(p) = layer.project {
+            if let Some(language) = p.language {
+                self.project.language = language;
+            }
+            if let Some(source) = p.source {
+                self.project.source = source;
+            }
+            if let Some(workdir) = p.workdir {
+                self.project.workdir = workdir;
+            }
+            self.project.exclude.extend(p.exclude);
+            if let Some(respect_gitignore) = p.respect_gitignore {
+                self.project.respect_gitignore = respect_gitignore;
+            }
+            if let Some(isolation) = p.isolation {
+                self.project.isolation = isolation;
+            }
+        }
+        if let Some(patch) = layer.patch {
+            self.patch.exclude.extend(patch.exclude);
+        }
+        if let Some(steps) = layer.steps {
+            match mode {
+                ExtendMergeMode::Append => self.steps.validate.extend(steps.validate),
+                ExtendMergeMode::Replace if !steps.validate.is_empty() => {
+                    self.steps.validate = steps.validate;
+                }
+                ExtendMergeMode::Replace => {}
+            }
+        }
+        match mode {
+            ExtendMergeMode::Append => self.mounts.extend(layer.mounts),
+            ExtendMergeMode::Replace if !layer.mounts.is_empty() => {
+                self.mounts = layer.mounts;
+            }
+            ExtendMergeMode::Replace => {}
+        }
+        if let Some(artifacts) = layer.artifacts {
+            match mode {
+                ExtendMergeMode::Append => self.artifacts.patterns.extend(artifacts.patterns),
+                ExtendMergeMode::Replace if !artifacts.patterns.is_empty() => {
+                    self.artifacts.patterns = artifacts.patterns;
+                }
+                ExtendMergeMode::Replace => {}
+            }
+        }
+    }
+
     pub fn default_rust() -> Self {
         Self {
             agent: AgentConfig::default(),
@@ -164,35 +802,54 @@ impl ForgeConfig {
                     ("CARGO_HOME".into(), "/home/forge/.cargo".into()),
                     ("RUSTUP_HOME".into(), "/home/forge/.rustup".into()),
                 ]),
+                secrets: vec![],
             },
             project: ProjectConfig {
                 language: "rust".into(),
                 source: ".".into(),
                 workdir: "/app".into(),
                 exclude: default_excludes(),
+                respect_gitignore: default_respect_gitignore(),
+                isolation: IsolationConfig::default(),
             },
             patch: PatchConfig::default(),
             mounts: vec![],
+            artifacts: ArtifactsConfig::default(),
+            forge: None,
             steps: StepsConfig {
                 validate: vec![
                     ValidateStep {
                         name: "check".into(),
                         command: "cargo check 2>&1".into(),
+                        timeout_secs: None,
+                        parallel: false,
+                        retries: 0,
                     },
                     ValidateStep {
                         name: "test".into(),
                         command: "cargo test 2>&1".into(),
+                        timeout_secs: None,
+                        parallel: false,
+                        retries: 0,
                     },
                     ValidateStep {
                         name: "bench".into(),
                         command: "cargo bench 2>&1".into(),
+                        timeout_secs: None,
+                        parallel: false,
+                        retries: 0,
                     },
                 ],
             },
         }
     }
 
-    fn validate(&self) -> Result<()> {
+    pub(crate) fn validate(&self, env: &EnvVars) -> Result<()> {
+        for name in self.container.referenced_secret_names() {
+            if env.get(name).is_none() {
+                return Err(missing_secret_error(name));
+            }
+        }
         if self.container.image.is_empty() {
             bail!("container.image must not be empty");
         }
@@ -208,6 +865,17 @@ impl ForgeConfig {
         if self.steps.validate.is_empty() {
             bail!("steps.validate must have at least one step");
         }
+        if let Some(forge) = &self.forge {
+            if forge.base_url.is_empty() {
+                bail!("forge.base_url must not be empty");
+            }
+            if forge.repo.is_empty() {
+                bail!("forge.repo must not be empty");
+            }
+            if forge.token_env.is_empty() {
+                bail!("forge.token_env must not be empty");
+            }
+        }
         for m in &self.mounts {
             if m.host.is_empty() {
                 bail!("mount host path must not be empty");
@@ -298,6 +966,39 @@ pub fn resolve_source_path(config: &ForgeConfig, config_dir: &Path) -> Result<Pa
     Ok(resolved)
 }
 
+/// expand `${VAR}` / `${VAR:-default}` references in `input` using `env`,
+/// erroring on an undefined variable with no default
+fn interpolate_str(input: &str, env: &EnvVars) -> Result<String> {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| eyre::eyre!("unterminated '${{' in config value: '{input}'"))?;
+        let inner = &after[..end];
+        let (name, default) = match inner.split_once(":-") {
+            Some((n, d)) => (n, Some(d)),
+            None => (inner, None),
+        };
+        let value = env
+            .get(name)
+            .map(str::to_string)
+            .or_else(|| default.map(str::to_string))
+            .ok_or_else(|| {
+                eyre::eyre!(
+                    "undefined variable '{name}' referenced in config \
+                     (use ${{{name}:-default}} to provide a fallback)"
+                )
+            })?;
+        out.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
 fn normalize_relative_path(path: &str) -> Result<PathBuf> {
     let mut out = PathBuf::new();
     for component in Path::new(path).components() {
@@ -374,4 +1075,279 @@ mod tests {
         assert!(matches!(config.backend, AgentBackend::Claude));
         assert_eq!(config.model, None);
     }
+
+    #[test]
+    fn test_interpolate_str_with_set_var() {
+        unsafe { std::env::set_var("FORGE_TEST_INTERPOLATE_VAR", "rust:1.80") };
+        let env = EnvVars::load().unwrap();
+        let out = interpolate_str("image: ${FORGE_TEST_INTERPOLATE_VAR}", &env).unwrap();
+        assert_eq!(out, "image: rust:1.80");
+        unsafe { std::env::remove_var("FORGE_TEST_INTERPOLATE_VAR") };
+    }
+
+    #[test]
+    fn test_interpolate_str_default_fallback() {
+        let env = EnvVars::load().unwrap();
+        let out = interpolate_str("${FORGE_TEST_UNDEFINED_VAR:-fallback}", &env).unwrap();
+        assert_eq!(out, "fallback");
+    }
+
+    #[test]
+    fn test_interpolate_str_undefined_errors() {
+        let env = EnvVars::load().unwrap();
+        let result = interpolate_str("${FORGE_TEST_UNDEFINED_VAR}", &env);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("undefined variable"));
+    }
+
+    #[test]
+    fn test_extends_merges_base_and_appends_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("base.toml"),
+            r#"
+                [container]
+                image = "rust:latest"
+                setup = []
+                user = "forge"
+                user_setup = "useradd forge"
+
+                [project]
+                language = "rust"
+                source = "."
+                workdir = "/app"
+
+                [steps]
+                validate = [{ name = "check", command = "cargo check" }]
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("child.toml"),
+            r#"
+                extends = "base.toml"
+
+                [container]
+                image = "rust:1.80"
+
+                [steps]
+                validate = [{ name = "test", command = "cargo test" }]
+            "#,
+        )
+        .unwrap();
+
+        let config = ForgeConfig::load(&dir.path().join("child.toml")).unwrap();
+        assert_eq!(config.container.image, "rust:1.80");
+        assert_eq!(config.container.user, "forge");
+        assert_eq!(config.steps.validate.len(), 2);
+        assert_eq!(config.steps.validate[0].name, "check");
+        assert_eq!(config.steps.validate[1].name, "test");
+    }
+
+    #[test]
+    fn test_extends_replace_mode_overrides_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("base.toml"),
+            r#"
+                [container]
+                image = "rust:latest"
+                setup = []
+                user = "forge"
+                user_setup = "useradd forge"
+
+                [project]
+                language = "rust"
+                source = "."
+                workdir = "/app"
+
+                [steps]
+                validate = [{ name = "check", command = "cargo check" }]
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("child.toml"),
+            r#"
+                extends = "base.toml"
+                merge = "replace"
+
+                [steps]
+                validate = [{ name = "test", command = "cargo test" }]
+            "#,
+        )
+        .unwrap();
+
+        let config = ForgeConfig::load(&dir.path().join("child.toml")).unwrap();
+        assert_eq!(config.steps.validate.len(), 1);
+        assert_eq!(config.steps.validate[0].name, "test");
+    }
+
+    #[test]
+    fn test_extends_cycle_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.toml"), r#"extends = "b.toml""#).unwrap();
+        std::fs::write(dir.path().join("b.toml"), r#"extends = "a.toml""#).unwrap();
+
+        let result = ForgeConfig::load(&dir.path().join("a.toml"));
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("cycle"));
+    }
+
+    /// a diamond — `app.toml` extends both `common.toml` and `ci.toml`, and both of those
+    /// extend the same shared `base.toml` — is not a cycle, just a base reused by two siblings.
+    /// This must resolve cleanly rather than falsely tripping cycle detection.
+    #[test]
+    fn test_extends_diamond_shared_base_not_a_cycle() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("base.toml"),
+            r#"
+                [container]
+                image = "rust:latest"
+                setup = []
+                user = "forge"
+                user_setup = "useradd forge"
+
+                [project]
+                language = "rust"
+                source = "."
+                workdir = "/app"
+
+                [steps]
+                validate = [{ name = "check", command = "cargo check" }]
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("common.toml"),
+            r#"
+                extends = "base.toml"
+
+                [steps]
+                validate = [{ name = "fmt", command = "cargo fmt --check" }]
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("ci.toml"),
+            r#"
+                extends = "base.toml"
+
+                [steps]
+                validate = [{ name = "test", command = "cargo test" }]
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("app.toml"),
+            r#"
+                extends = ["common.toml", "ci.toml"]
+            "#,
+        )
+        .unwrap();
+
+        let config = ForgeConfig::load(&dir.path().join("app.toml")).unwrap();
+        assert_eq!(config.container.image, "rust:latest");
+        assert_eq!(config.steps.validate.len(), 4);
+        let names: Vec<&str> = config.steps.validate.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["check", "fmt", "check", "test"]);
+    }
+
+    #[test]
+    fn test_resolve_secrets_from_list_and_marker() {
+        unsafe { std::env::set_var("FORGE_TEST_SECRET_TOKEN", "sekrit") };
+        let env = EnvVars::load().unwrap();
+        let container = ContainerConfig {
+            image: "x".into(),
+            setup: vec![],
+            user: "x".into(),
+            user_setup: "x".into(),
+            env: HashMap::from([(
+                "API_TOKEN".to_string(),
+                "${secret:FORGE_TEST_SECRET_TOKEN}".to_string(),
+            )]),
+            secrets: vec!["FORGE_TEST_SECRET_TOKEN".to_string()],
+        };
+        let resolved = container.resolve_secrets(&env).unwrap();
+        assert_eq!(
+            resolved.get("FORGE_TEST_SECRET_TOKEN").map(String::as_str),
+            Some("sekrit")
+        );
+        assert_eq!(resolved.get("API_TOKEN").map(String::as_str), Some("sekrit"));
+        unsafe { std::env::remove_var("FORGE_TEST_SECRET_TOKEN") };
+    }
+
+    #[test]
+    fn test_resolve_secrets_missing_errors() {
+        let env = EnvVars::load().unwrap();
+        let container = ContainerConfig {
+            image: "x".into(),
+            setup: vec![],
+            user: "x".into(),
+            user_setup: "x".into(),
+            env: HashMap::new(),
+            secrets: vec!["FORGE_TEST_DEFINITELY_UNSET".to_string()],
+        };
+        let err = container.resolve_secrets(&env).unwrap_err();
+        assert!(err.to_string().contains("FORGE_TEST_DEFINITELY_UNSET"));
+    }
+
+    #[test]
+    fn test_string_list_splits_bare_string_on_whitespace() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            #[serde(deserialize_with = "deserialize_string_list")]
+            exclude: Vec<String>,
+        }
+
+        let from_string: Wrapper = toml::from_str(r#"exclude = "target node_modules""#).unwrap();
+        assert_eq!(from_string.exclude, vec!["target".to_string(), "node_modules".to_string()]);
+
+        let from_list: Wrapper = toml::from_str(r#"exclude = ["target/**", "node_modules/**"]"#).unwrap();
+        assert_eq!(from_list.exclude, vec!["target/**".to_string(), "node_modules/**".to_string()]);
+    }
+
+    #[test]
+    fn test_container_setup_keeps_bare_string_as_one_command() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            #[serde(deserialize_with = "deserialize_command_list")]
+            setup: Vec<String>,
+        }
+
+        let from_string: Wrapper =
+            toml::from_str(r#"setup = "apt-get update && apt-get install -y git""#).unwrap();
+        assert_eq!(from_string.setup, vec!["apt-get update && apt-get install -y git".to_string()]);
+
+        let from_list: Wrapper =
+            toml::from_str(r#"setup = ["apt-get update", "apt-get install -y git"]"#).unwrap();
+        assert_eq!(
+            from_list.setup,
+            vec![
+                "apt-get update".to_string(),
+                "apt-get install -y git".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_step_command_array_joined_with_newlines() {
+        let toml_str = r#"
+            name = "test"
+            command = ["cargo build", "cargo test"]
+        "#;
+        let step: ValidateStep = toml::from_str(toml_str).unwrap();
+        assert_eq!(step.command, "cargo build\ncargo test");
+    }
+
+    #[test]
+    fn test_validate_step_command_string_unchanged() {
+        let toml_str = r#"
+            name = "test"
+            command = "cargo test"
+        "#;
+        let step: ValidateStep = toml::from_str(toml_str).unwrap();
+        assert_eq!(step.command, "cargo test");
+    }
 }
@@ -1,7 +1,8 @@
-use crate::config::{AgentBackend, ForgeConfig};
+use crate::config::{AgentBackend, ForgeConfig, is_secret_marker};
 use dagger_sdk::{DaggerConn, HostDirectoryOpts};
 use edda_sandbox::DaggerSandbox;
 use eyre::Result;
+use std::collections::HashMap;
 use std::path::Path;
 
 fn sh(cmd: &str) -> Vec<String> {
@@ -21,6 +22,7 @@ pub async fn setup_container(
     config: &ForgeConfig,
     source_path: &Path,
     config_dir: &Path,
+    secrets: &HashMap<String, String>,
 ) -> Result<DaggerSandbox> {
     let exclude_refs: Vec<&str> = config.project.exclude.iter().map(|s| s.as_str()).collect();
     let source_dir = if exclude_refs.is_empty() {
@@ -93,8 +95,16 @@ pub async fn setup_container(
     // switch to user
     ctr = ctr.with_user(user);
 
-    // set env vars
+    // set env vars (skip any value that is itself a ${secret:NAME} marker — resolved below)
     for (key, value) in &config.container.env {
+        if is_secret_marker(value) {
+            continue;
+        }
+        ctr = ctr.with_env_variable(key, value);
+    }
+
+    // inject resolved host secrets; never logged, never written into the generated config
+    for (key, value) in secrets {
         ctr = ctr.with_env_variable(key, value);
     }
 
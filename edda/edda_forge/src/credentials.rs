@@ -0,0 +1,120 @@
+use crate::config::{AgentBackend, AgentConfig};
+use edda_mcp::env::EnvVars;
+use eyre::Result;
+
+/// bridges edda_mcp's `EnvVars` (which knows nothing about agent backends) into
+/// edda_forge's `AgentConfig`, since the two types live in different crates.
+pub trait EnvVarsExt {
+    /// check that the selected agent backend has the credentials it needs before a
+    /// container spins up, failing fast with an actionable message if not
+    fn validate_agent(&self, cfg: &AgentConfig) -> Result<()>;
+}
+
+impl EnvVarsExt for EnvVars {
+    fn validate_agent(&self, cfg: &AgentConfig) -> Result<()> {
+        match &cfg.backend {
+            AgentBackend::Claude => {
+                let has_key = self.get("ANTHROPIC_API_KEY").is_some();
+                let has_bedrock = self.get("CLAUDE_CODE_USE_BEDROCK").is_some()
+                    && self.get("AWS_ACCESS_KEY_ID").is_some();
+                let has_vertex = self.get("CLAUDE_CODE_USE_VERTEX").is_some()
+                    && self.get("GOOGLE_APPLICATION_CREDENTIALS").is_some();
+                if !has_key && !has_bedrock && !has_vertex {
+                    eyre::bail!(
+                        "ANTHROPIC_API_KEY not set (and no Bedrock/Vertex credentials found: \
+                         CLAUDE_CODE_USE_BEDROCK+AWS_ACCESS_KEY_ID or \
+                         CLAUDE_CODE_USE_VERTEX+GOOGLE_APPLICATION_CREDENTIALS). \
+                         Please add it to ~/.edda/.env or system environment.\n\
+                         See ~/.edda/.env.example for template."
+                    );
+                }
+            }
+            AgentBackend::OpenCode => {
+                let provider = cfg
+                    .model
+                    .as_deref()
+                    .and_then(|m| m.split_once('/'))
+                    .map(|(provider, _)| provider)
+                    .unwrap_or("anthropic");
+                let key_var = match provider {
+                    // opencode's own hosted free-tier models (e.g. `opencode/kimi-k2.5-free`,
+                    // the format documented in .env.example) need no credential at all
+                    "opencode" => return Ok(()),
+                    "anthropic" => "ANTHROPIC_API_KEY",
+                    "openai" => "OPENAI_API_KEY",
+                    "google" | "gemini" => "GOOGLE_API_KEY",
+                    "kimi" | "moonshot" => "MOONSHOT_API_KEY",
+                    "openrouter" => "OPENROUTER_API_KEY",
+                    other => {
+                        tracing::debug!(
+                            provider = other,
+                            "unrecognized opencode model provider, skipping credential preflight"
+                        );
+                        return Ok(());
+                    }
+                };
+                if self.get(key_var).is_none() {
+                    eyre::bail!(
+                        "{key_var} not set (required for opencode model provider '{provider}'). \
+                         Please add it to ~/.edda/.env or system environment.\n\
+                         See ~/.edda/.env.example for template."
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_agent_claude_missing_key_errors() {
+        unsafe { std::env::remove_var("ANTHROPIC_API_KEY") };
+        unsafe { std::env::remove_var("CLAUDE_CODE_USE_BEDROCK") };
+        unsafe { std::env::remove_var("CLAUDE_CODE_USE_VERTEX") };
+        let env = EnvVars::load().unwrap();
+        let cfg = AgentConfig {
+            backend: AgentBackend::Claude,
+            model: None,
+        };
+        let err = env.validate_agent(&cfg).unwrap_err();
+        assert!(err.to_string().contains("ANTHROPIC_API_KEY"));
+    }
+
+    #[test]
+    fn test_validate_agent_opencode_unrecognized_provider_skips() {
+        let env = EnvVars::load().unwrap();
+        let cfg = AgentConfig {
+            backend: AgentBackend::OpenCode,
+            model: Some("some-unknown-provider/model-x".to_string()),
+        };
+        assert!(env.validate_agent(&cfg).is_ok());
+    }
+
+    #[test]
+    fn test_validate_agent_opencode_free_tier_model_needs_no_key() {
+        // the canonical spec from config.rs's own doc comment and test:
+        // "opencode:opencode/kimi-k2.5-free" deserializes to this model string
+        let env = EnvVars::load().unwrap();
+        let cfg = AgentConfig {
+            backend: AgentBackend::OpenCode,
+            model: Some("opencode/kimi-k2.5-free".to_string()),
+        };
+        assert!(env.validate_agent(&cfg).is_ok());
+    }
+
+    #[test]
+    fn test_validate_agent_opencode_moonshot_missing_key_errors() {
+        unsafe { std::env::remove_var("MOONSHOT_API_KEY") };
+        let env = EnvVars::load().unwrap();
+        let cfg = AgentConfig {
+            backend: AgentBackend::OpenCode,
+            model: Some("moonshot/kimi-k2.5".to_string()),
+        };
+        let err = env.validate_agent(&cfg).unwrap_err();
+        assert!(err.to_string().contains("MOONSHOT_API_KEY"));
+    }
+}
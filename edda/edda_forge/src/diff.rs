@@ -0,0 +1,164 @@
+//! A minimal unified-diff parser: just enough of `git diff`'s output format (`+++ b/<path>`
+//! file headers, `@@ -a,b +c,d @@` hunk headers, `+`/`-`/` ` line prefixes) to give the review
+//! step compact, line-numbered context instead of a wall of raw diff text.
+
+/// one line within a [`Hunk`], tagged with the line number it lands on in the new file
+/// (context and added lines only — removed lines don't exist in the new file)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: LineKind,
+    pub new_line: Option<u32>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+/// one `@@ -a,b +c,d @@` hunk, anchored at the first line number it touches in the new file
+#[derive(Debug, Clone, Default)]
+pub struct Hunk {
+    pub new_start: u32,
+    pub lines: Vec<DiffLine>,
+}
+
+/// all hunks touching one file in a unified diff
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    pub path: String,
+    pub hunks: Vec<Hunk>,
+}
+
+/// parse `git diff`'s unified-diff output into one [`FileDiff`] per touched file
+pub fn parse_unified_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files = Vec::new();
+    let mut current: Option<FileDiff> = None;
+    let mut hunk: Option<Hunk> = None;
+    let mut new_line = 0u32;
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            // a new file section starts: its `--- a/` header is not a removed line of the last hunk
+            finish_hunk(&mut current, &mut hunk);
+            if let Some(f) = current.take() {
+                files.push(f);
+            }
+        } else if let Some(path) = line.strip_prefix("+++ b/") {
+            finish_hunk(&mut current, &mut hunk);
+            if let Some(f) = current.take() {
+                files.push(f);
+            }
+            current = Some(FileDiff { path: path.to_string(), hunks: Vec::new() });
+        } else if let Some(header) = line.strip_prefix("@@ ") {
+            finish_hunk(&mut current, &mut hunk);
+            new_line = parse_hunk_new_start(header).unwrap_or(1);
+            hunk = Some(Hunk { new_start: new_line, lines: Vec::new() });
+        } else if let Some(h) = hunk.as_mut() {
+            if let Some(text) = line.strip_prefix('+') {
+                h.lines.push(DiffLine { kind: LineKind::Added, new_line: Some(new_line), text: text.to_string() });
+                new_line += 1;
+            } else if let Some(text) = line.strip_prefix('-') {
+                h.lines.push(DiffLine { kind: LineKind::Removed, new_line: None, text: text.to_string() });
+            } else if let Some(text) = line.strip_prefix(' ') {
+                h.lines.push(DiffLine { kind: LineKind::Context, new_line: Some(new_line), text: text.to_string() });
+                new_line += 1;
+            }
+            // other lines ("\ No newline at end of file", etc.) carry no content, skip
+        }
+    }
+    finish_hunk(&mut current, &mut hunk);
+    if let Some(f) = current.take() {
+        files.push(f);
+    }
+    files
+}
+
+fn finish_hunk(current: &mut Option<FileDiff>, hunk: &mut Option<Hunk>) {
+    if let Some(h) = hunk.take() {
+        if let Some(f) = current.as_mut() {
+            f.hunks.push(h);
+        }
+    }
+}
+
+fn parse_hunk_new_start(header: &str) -> Option<u32> {
+    // `header` is "-a,b +c,d @@ ..." (the leading "@@ " already stripped)
+    let plus = header.split_whitespace().find(|p| p.starts_with('+'))?;
+    let start = plus.trim_start_matches('+').split(',').next()?;
+    start.parse().ok()
+}
+
+/// render compact, line-numbered per-file/per-hunk context suitable for a review prompt
+pub fn render_for_review(files: &[FileDiff]) -> String {
+    let mut out = String::new();
+    for file in files {
+        out.push_str(&format!("--- {}\n", file.path));
+        for hunk in &file.hunks {
+            out.push_str(&format!("  @@ new file line {} @@\n", hunk.new_start));
+            for line in &hunk.lines {
+                let marker = match line.kind {
+                    LineKind::Added => '+',
+                    LineKind::Removed => '-',
+                    LineKind::Context => ' ',
+                };
+                match line.new_line {
+                    Some(n) => out.push_str(&format!("  {n:>5} {marker} {}\n", line.text)),
+                    None => out.push_str(&format!("        {marker} {}\n", line.text)),
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -10,3 +10,4 @@ fn main() {
+     let a = 1;
+-    let b = 2;
++    let b = 3;
++    let c = 4;
+ }
+";
+
+    #[test]
+    fn test_parses_file_and_hunk_headers() {
+        let files = parse_unified_diff(SAMPLE);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].hunks.len(), 1);
+        assert_eq!(files[0].hunks[0].new_start, 10);
+    }
+
+    #[test]
+    fn test_assigns_new_file_line_numbers() {
+        let files = parse_unified_diff(SAMPLE);
+        let lines = &files[0].hunks[0].lines;
+        assert_eq!(lines[0].kind, LineKind::Context);
+        assert_eq!(lines[0].new_line, Some(10));
+        assert_eq!(lines[1].kind, LineKind::Removed);
+        assert_eq!(lines[1].new_line, None);
+        assert_eq!(lines[2].kind, LineKind::Added);
+        assert_eq!(lines[2].new_line, Some(11));
+        assert_eq!(lines[3].kind, LineKind::Added);
+        assert_eq!(lines[3].new_line, Some(12));
+    }
+
+    #[test]
+    fn test_render_for_review_includes_path_and_line_numbers() {
+        let files = parse_unified_diff(SAMPLE);
+        let rendered = render_for_review(&files);
+        assert!(rendered.contains("--- src/lib.rs"));
+        assert!(rendered.contains("11 +"));
+    }
+}
@@ -0,0 +1,106 @@
+use eyre::Result;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// one line of machine-readable progress, emitted alongside (not instead of) the human
+/// `tracing` logs, so external orchestrators can drive/monitor a forge run programmatically
+/// instead of scraping log text
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ForgeEvent<'a> {
+    Transition {
+        from: &'a str,
+        to: &'a str,
+        /// how many times the backtrack edge that produced this transition has fired, or 0
+        /// if this transition isn't a retry edge
+        retry_count: usize,
+    },
+    Exec {
+        step: &'a str,
+        exit_code: isize,
+        stdout_bytes: usize,
+        stderr_bytes: usize,
+    },
+}
+
+pub trait EventSink {
+    fn emit(&self, event: &ForgeEvent<'_>);
+}
+
+/// discards every event; the default when `--event-log` is not set
+pub struct NullEventSink;
+
+impl EventSink for NullEventSink {
+    fn emit(&self, _event: &ForgeEvent<'_>) {}
+}
+
+/// appends one JSON object per line to a file
+pub struct JsonLinesEventSink {
+    file: Mutex<File>,
+}
+
+impl JsonLinesEventSink {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl EventSink for JsonLinesEventSink {
+    fn emit(&self, event: &ForgeEvent<'_>) {
+        let Ok(line) = serde_json::to_string(event) else {
+            return;
+        };
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(file, "{line}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transition_event_serializes_with_tag() {
+        let event = ForgeEvent::Transition {
+            from: "Validate(step=2)",
+            to: "Work",
+            retry_count: 1,
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains(r#""event":"transition""#));
+        assert!(json.contains(r#""from":"Validate(step=2)""#));
+        assert!(json.contains(r#""retry_count":1"#));
+    }
+
+    #[test]
+    fn test_json_lines_sink_appends_one_line_per_event() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        let sink = JsonLinesEventSink::create(&path).unwrap();
+
+        sink.emit(&ForgeEvent::Exec {
+            step: "test",
+            exit_code: 0,
+            stdout_bytes: 10,
+            stderr_bytes: 0,
+        });
+        sink.emit(&ForgeEvent::Exec {
+            step: "check",
+            exit_code: 1,
+            stdout_bytes: 0,
+            stderr_bytes: 842,
+        });
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains(r#""stderr_bytes":842"#));
+    }
+}
@@ -0,0 +1,102 @@
+use edda_sandbox::{ExecResult, Sandbox};
+use eyre::Result;
+use mlua::{Lua, Value};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// the result of evaluating a project's `forge.lua`: whether validation passed, any recorded
+/// metrics/artifacts, and (on failure) the reason to fold into the next fix task
+pub struct GoodfileVerdict {
+    pub passed: bool,
+    pub reason: Option<String>,
+    pub metrics: HashMap<String, f64>,
+    pub artifacts: Vec<String>,
+}
+
+/// evaluate `script_path` against the live sandbox, once per `State::Validate` entry.
+///
+/// The script gets four globals: `run(cmd)` proxies to `sandbox.exec` and returns a table with
+/// `exit_code`/`stdout`/`stderr`; `metric(name, value)` records a number; `artifact(path)`
+/// registers an output file; `fail(reason)` marks the run failed without stopping the script
+/// early (a script that wants to stop immediately can still `error()`). A script may also
+/// return a verdict table (`{pass = false, reason = "..."}`) or a plain boolean instead of
+/// calling `fail`.
+///
+/// `run`'s callback blocks the calling thread on `sandbox.exec`'s future via `block_in_place`,
+/// since Lua has no notion of cooperative `.await`; this requires the multi-threaded Tokio
+/// runtime `#[tokio::main]` already gives us.
+pub fn evaluate<S: Sandbox>(script_path: &Path, sandbox: &mut S) -> Result<GoodfileVerdict> {
+    let script = std::fs::read_to_string(script_path)
+        .map_err(|e| eyre::eyre!("failed to read goodfile '{}': {e}", script_path.display()))?;
+
+    let lua = Lua::new();
+    let passed = Cell::new(true);
+    let fail_reason = RefCell::new(None::<String>);
+    let metrics = RefCell::new(HashMap::new());
+    let artifacts = RefCell::new(Vec::new());
+
+    let result = lua.scope(|scope| {
+        let globals = lua.globals();
+
+        let run_fn = scope.create_function_mut(|lua, cmd: String| {
+            let result: Result<ExecResult> = tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(sandbox.exec(&cmd))
+            });
+            let result = result.map_err(mlua::Error::external)?;
+            let table = lua.create_table()?;
+            table.set("exit_code", result.exit_code)?;
+            table.set("stdout", result.stdout)?;
+            table.set("stderr", result.stderr)?;
+            Ok(table)
+        })?;
+        globals.set("run", run_fn)?;
+
+        let metric_fn = scope.create_function_mut(|_, (name, value): (String, f64)| {
+            metrics.borrow_mut().insert(name, value);
+            Ok(())
+        })?;
+        globals.set("metric", metric_fn)?;
+
+        let artifact_fn = scope.create_function_mut(|_, path: String| {
+            artifacts.borrow_mut().push(path);
+            Ok(())
+        })?;
+        globals.set("artifact", artifact_fn)?;
+
+        let fail_fn = scope.create_function_mut(|_, reason: String| {
+            passed.set(false);
+            *fail_reason.borrow_mut() = Some(reason);
+            Ok(())
+        })?;
+        globals.set("fail", fail_fn)?;
+
+        let return_value: Value = lua
+            .load(&script)
+            .set_name(&script_path.to_string_lossy())
+            .eval()?;
+
+        match return_value {
+            Value::Table(t) => {
+                if let Ok(false) = t.get::<_, bool>("pass") {
+                    passed.set(false);
+                }
+                if let Ok(reason) = t.get::<_, String>("reason") {
+                    *fail_reason.borrow_mut() = Some(reason);
+                }
+            }
+            Value::Boolean(false) => passed.set(false),
+            _ => {}
+        }
+
+        Ok(())
+    });
+    result.map_err(|e| eyre::eyre!("forge.lua failed: {e}"))?;
+
+    Ok(GoodfileVerdict {
+        passed: passed.get(),
+        reason: fail_reason.into_inner(),
+        metrics: metrics.into_inner(),
+        artifacts: artifacts.into_inner(),
+    })
+}
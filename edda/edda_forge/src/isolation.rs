@@ -0,0 +1,321 @@
+//! Best-effort Linux namespace/seccomp confinement for commands run by [`crate::local::LocalSandbox`].
+//!
+//! The dagger runtime already runs every command inside a container, so this only matters for
+//! `--runtime local`, where `exec` otherwise hands arbitrary shell strings to `sh -c` with the
+//! host's full filesystem and network reachable. Opt-in via `project.isolation.enabled`; degrades
+//! to the unconfined `sh -c` path whenever the kernel or current privilege level doesn't support
+//! it, rather than failing the run.
+
+use std::path::PathBuf;
+
+/// resolved, ready-to-apply isolation settings for one [`crate::local::LocalSandbox`]. Built once
+/// in `setup_local_sandbox` from `project.isolation` plus [`is_supported`].
+#[derive(Debug, Clone)]
+pub struct IsolationOptions {
+    /// allow network access inside the isolated namespace; when false, `CLONE_NEWNET` gives the
+    /// command a private namespace with only loopback, and raw socket creation is seccomp-denied
+    pub network: bool,
+    /// extra host paths bind-mounted read-only into the isolated root, at the same absolute path
+    pub readonly_paths: Vec<PathBuf>,
+}
+
+/// true if this process can plausibly set up rootless namespace isolation: Linux on x86_64, with
+/// unprivileged `CLONE_NEWUSER` not disabled by sysctl (some distros turn it off by default).
+pub fn is_supported() -> bool {
+    if !(cfg!(target_os = "linux") && cfg!(target_arch = "x86_64")) {
+        return false;
+    }
+    std::fs::read_to_string("/proc/sys/kernel/unprivileged_userns_clone")
+        .map(|v| v.trim() == "1")
+        .unwrap_or(true) // sysctl doesn't exist on kernels where it's unconditionally allowed
+}
+
+#[cfg(target_os = "linux")]
+pub use linux::enter;
+
+#[cfg(not(target_os = "linux"))]
+pub fn enter(_root: &std::path::Path, _options: &IsolationOptions) -> std::io::Result<()> {
+    Err(std::io::Error::other("isolation is only implemented on Linux"))
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::IsolationOptions;
+    use std::io;
+    use std::path::Path;
+
+    /// runs in the forked child, via `Command::pre_exec`, after `fork` but before `execve`. Only
+    /// async-signal-safe operations are safe here: no allocation beyond what's already on the
+    /// stack/heap from before the fork, no locks. The raw syscalls below are what every rootless
+    /// container runtime (bubblewrap, runc --rootless, ...) does in the equivalent spot.
+    pub fn enter(root: &Path, options: &IsolationOptions) -> io::Result<()> {
+        let mut flags = libc::CLONE_NEWUSER | libc::CLONE_NEWNS | libc::CLONE_NEWPID;
+        if !options.network {
+            flags |= libc::CLONE_NEWNET;
+        }
+        unshare(flags)?;
+        map_self_to_root()?;
+
+        // `CLONE_NEWPID` only affects children created after the unshare, not the calling
+        // process itself — fork once more so the process that ends up calling `execve` is PID 1
+        // of the fresh namespace instead of still living in the old one.
+        match unsafe { libc::fork() } {
+            -1 => Err(io::Error::last_os_error()),
+            0 => enter_child(root, options),
+            child => {
+                let mut status = 0i32;
+                loop {
+                    let ret = unsafe { libc::waitpid(child, &mut status, 0) };
+                    if ret >= 0 {
+                        break;
+                    }
+                    if io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
+                        break;
+                    }
+                }
+                let code = if libc::WIFEXITED(status) {
+                    libc::WEXITSTATUS(status)
+                } else {
+                    128 + libc::WTERMSIG(status)
+                };
+                unsafe { libc::_exit(code) }
+            }
+        }
+    }
+
+    /// bind-mounted read-only into every isolated root so `execve` (and whatever dynamic linker
+    /// it pulls in) still resolves after `pivot_root` — without these the pivoted root is an
+    /// empty tmp tree and every exec fails with ENOENT. Not every entry exists on every distro
+    /// (e.g. no separate `/lib64`), so `populate_minimal_rootfs` skips whichever don't.
+    const MINIMAL_ROOTFS_DIRS: &[&str] = &["/usr", "/bin", "/sbin", "/lib", "/lib64", "/etc"];
+
+    /// the new namespace's PID 1: private the mount tree, bind-mount a minimal host rootfs plus
+    /// `readonly_paths` into `root`, pivot into it, mount a fresh `/proc`, and install the
+    /// seccomp filter. Runs once, then returns control to `Command::pre_exec`'s caller, which
+    /// immediately `execve`s the target.
+    ///
+    /// The bind mounts happen *before* `pivot_into`, targeting paths under `root` rather than
+    /// their live absolute locations: `pivot_root` carries submounts of the new root across the
+    /// pivot, so a bind mounted at `root/usr` is still there, now addressable as `/usr`, once
+    /// `root` itself becomes `/`. Mounting at the real absolute path instead (i.e. after the
+    /// pivot) can't work — by then that path resolves inside the already-pivoted, still-empty
+    /// tree, not the host filesystem the bind is supposed to expose.
+    fn enter_child(root: &Path, options: &IsolationOptions) -> io::Result<()> {
+        mount_private_root()?;
+        populate_minimal_rootfs(root)?;
+        for path in &options.readonly_paths {
+            bind_mount_tree(path, &target_in_root(root, path), true)?;
+        }
+        pivot_into(root)?;
+        mount_proc()?;
+        install_seccomp_filter(options.network)?;
+        Ok(())
+    }
+
+    /// maps a host absolute path to its future location under `root`, i.e. where it needs to be
+    /// bind-mounted pre-pivot so it lands at the same absolute path once `root` becomes `/`.
+    fn target_in_root(root: &Path, host_path: &Path) -> std::path::PathBuf {
+        match host_path.strip_prefix("/") {
+            Ok(rel) => root.join(rel),
+            Err(_) => root.join(host_path),
+        }
+    }
+
+    fn populate_minimal_rootfs(root: &Path) -> io::Result<()> {
+        for dir in MINIMAL_ROOTFS_DIRS {
+            let host_path = Path::new(dir);
+            if !host_path.exists() {
+                continue;
+            }
+            bind_mount_tree(host_path, &target_in_root(root, host_path), true)?;
+        }
+        Ok(())
+    }
+
+    fn unshare(flags: libc::c_int) -> io::Result<()> {
+        if unsafe { libc::unshare(flags) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// maps our own (host) uid/gid to root inside the new user namespace, the standard
+    /// single-mapping trick unprivileged user namespaces use instead of a real `/etc/subuid`
+    /// range. Must happen before the `CLONE_NEWPID` fork below, while we're still the namespace's
+    /// sole process.
+    fn map_self_to_root() -> io::Result<()> {
+        let uid = unsafe { libc::getuid() };
+        let gid = unsafe { libc::getgid() };
+        std::fs::write("/proc/self/setgroups", b"deny")?;
+        std::fs::write("/proc/self/uid_map", format!("0 {uid} 1"))?;
+        std::fs::write("/proc/self/gid_map", format!("0 {gid} 1"))?;
+        Ok(())
+    }
+
+    fn mount_private_root() -> io::Result<()> {
+        mount(c"none", c"/", c"", libc::MS_REC | libc::MS_PRIVATE, std::ptr::null())
+    }
+
+    /// bind-mounts `host_path` onto `target` (creating it if needed) and, if `read_only`,
+    /// immediately remounts it read-only. The read-only flag survives a later `pivot_root` since
+    /// that only changes which mount is `/`, not the flags already set on its submounts.
+    fn bind_mount_tree(host_path: &Path, target: &Path, read_only: bool) -> io::Result<()> {
+        std::fs::create_dir_all(target)?;
+        let c_host = path_to_cstring(host_path)?;
+        let c_target = path_to_cstring(target)?;
+        mount(&c_host, &c_target, c"", libc::MS_BIND | libc::MS_REC, std::ptr::null())?;
+        if read_only {
+            mount(
+                c"none",
+                &c_target,
+                c"",
+                libc::MS_BIND | libc::MS_REMOUNT | libc::MS_RDONLY | libc::MS_REC,
+                std::ptr::null(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// bind-mounts `root` onto itself (so it's a mount point `pivot_root` can target), then
+    /// pivots into it and unmounts the old root from inside the new one.
+    fn pivot_into(root: &Path) -> io::Result<()> {
+        let c_root = path_to_cstring(root)?;
+        mount(&c_root, &c_root, c"", libc::MS_BIND | libc::MS_REC, std::ptr::null())?;
+
+        if unsafe { libc::chdir(c_root.as_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let old_root = root.join(".old-root");
+        std::fs::create_dir_all(&old_root)?;
+        let c_old_root = path_to_cstring(&old_root)?;
+
+        // new_root == "." since we've already chdir'd into it
+        if unsafe { libc::syscall(libc::SYS_pivot_root, c".".as_ptr(), c_old_root.as_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if unsafe { libc::chdir(c"/".as_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if unsafe { libc::umount2(c"/.old-root".as_ptr(), libc::MNT_DETACH) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        unsafe { libc::rmdir(c"/.old-root".as_ptr()) };
+        Ok(())
+    }
+
+    fn mount_proc() -> io::Result<()> {
+        std::fs::create_dir_all("/proc").ok();
+        mount(c"proc", c"/proc", c"proc", 0, std::ptr::null())
+    }
+
+    fn mount(
+        source: &std::ffi::CStr,
+        target: &std::ffi::CStr,
+        fstype: &std::ffi::CStr,
+        flags: libc::c_ulong,
+        data: *const libc::c_void,
+    ) -> io::Result<()> {
+        let ret = unsafe {
+            libc::mount(
+                source.as_ptr(),
+                target.as_ptr(),
+                if fstype.to_bytes().is_empty() { std::ptr::null() } else { fstype.as_ptr() },
+                flags,
+                data,
+            )
+        };
+        if ret != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn path_to_cstring(path: &Path) -> io::Result<std::ffi::CString> {
+        std::ffi::CString::new(path.as_os_str().as_encoded_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    }
+
+    /// default-allow seccomp-bpf filter that denies a short list of syscalls dangerous to run
+    /// against the host even from inside the namespaces set up above: `mount`/`umount2` (could
+    /// remount the pivoted-away host root), `ptrace` (could inspect processes outside the pid
+    /// namespace via `/proc`), `kexec_load`/`kexec_file_load` (replaces the running kernel), and,
+    /// when `network` is disabled, raw `AF_PACKET` sockets (packet capture/spoofing that a
+    /// network namespace alone doesn't prevent).
+    fn install_seccomp_filter(network: bool) -> io::Result<()> {
+        const AUDIT_ARCH_X86_64: u32 = 0xC000_003E;
+        const SECCOMP_DATA_NR_OFFSET: u32 = 0;
+        const SECCOMP_DATA_ARCH_OFFSET: u32 = 4;
+        const SECCOMP_DATA_ARGS0_LO_OFFSET: u32 = 16; // low 32 bits of args[0], little-endian
+
+        const AF_PACKET: u32 = 17;
+
+        const SYS_MOUNT: u32 = libc::SYS_mount as u32;
+        const SYS_UMOUNT2: u32 = libc::SYS_umount2 as u32;
+        const SYS_PTRACE: u32 = libc::SYS_ptrace as u32;
+        const SYS_KEXEC_LOAD: u32 = libc::SYS_kexec_load as u32;
+        const SYS_KEXEC_FILE_LOAD: u32 = libc::SYS_kexec_file_load as u32;
+        const SYS_SOCKET: u32 = libc::SYS_socket as u32;
+
+        let stmt = |code: u16, k: u32| libc::sock_filter { code, jt: 0, jf: 0, k };
+        let jump = |code: u16, k: u32, jt: u8, jf: u8| libc::sock_filter { code, jt, jf, k };
+
+        const BPF_LD_W_ABS: u16 = (libc::BPF_LD | libc::BPF_W | libc::BPF_ABS) as u16;
+        const BPF_JMP_JEQ_K: u16 = (libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K) as u16;
+        const BPF_RET_K: u16 = (libc::BPF_RET | libc::BPF_K) as u16;
+
+        let mut prog = vec![
+            // validate the syscall's audit arch up front; a mismatch means a 32-bit compat
+            // syscall snuck through a path we haven't reasoned about, so kill rather than allow
+            stmt(BPF_LD_W_ABS, SECCOMP_DATA_ARCH_OFFSET),
+            jump(BPF_JMP_JEQ_K, AUDIT_ARCH_X86_64, 1, 0),
+            stmt(BPF_RET_K, libc::SECCOMP_RET_KILL_PROCESS),
+            stmt(BPF_LD_W_ABS, SECCOMP_DATA_NR_OFFSET),
+        ];
+
+        let deny_syscall = |prog: &mut Vec<libc::sock_filter>, nr: u32| {
+            // `nr` is already loaded into the accumulator above; jump past the deny-return if it
+            // doesn't match, otherwise fall into it
+            prog.push(jump(BPF_JMP_JEQ_K, nr, 0, 1));
+            prog.push(stmt(BPF_RET_K, libc::SECCOMP_RET_ERRNO | (libc::EPERM as u32 & 0xffff)));
+        };
+
+        for nr in [SYS_MOUNT, SYS_UMOUNT2, SYS_PTRACE, SYS_KEXEC_LOAD, SYS_KEXEC_FILE_LOAD] {
+            deny_syscall(&mut prog, nr);
+            // each deny_syscall's first instruction needs the nr reloaded since BPF has no stack
+            prog.push(stmt(BPF_LD_W_ABS, SECCOMP_DATA_NR_OFFSET));
+        }
+        prog.pop(); // the trailing reload after the last syscall check is unused
+
+        if !network {
+            prog.push(jump(BPF_JMP_JEQ_K, SYS_SOCKET, 0, 3));
+            prog.push(stmt(BPF_LD_W_ABS, SECCOMP_DATA_ARGS0_LO_OFFSET));
+            prog.push(jump(BPF_JMP_JEQ_K, AF_PACKET, 0, 1));
+            prog.push(stmt(BPF_RET_K, libc::SECCOMP_RET_ERRNO | (libc::EAFNOSUPPORT as u32 & 0xffff)));
+        }
+
+        prog.push(stmt(BPF_RET_K, libc::SECCOMP_RET_ALLOW));
+
+        let fprog = libc::sock_fprog {
+            len: prog.len() as u16,
+            filter: prog.as_mut_ptr(),
+        };
+
+        if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let ret = unsafe {
+            libc::prctl(
+                libc::PR_SET_SECCOMP,
+                libc::SECCOMP_MODE_FILTER,
+                &fprog as *const libc::sock_fprog as libc::c_ulong,
+                0,
+                0,
+            )
+        };
+        if ret != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
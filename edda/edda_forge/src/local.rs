@@ -1,23 +1,51 @@
-use crate::config::{AgentBackend, ForgeConfig, MountConfig};
+use crate::config::{AgentBackend, ForgeConfig, IsolationConfig, MountConfig, is_secret_marker};
 use crate::container::AgentAuth;
-use edda_sandbox::{ExecResult, Sandbox};
+use crate::isolation;
+use edda_sandbox::{ExecResult, Sandbox, ShellChunk};
 use eyre::{Result, bail};
 use globset::{GlobSet, GlobSetBuilder};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
 use tokio::process::Command;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
+
+/// how long to keep accumulating filesystem events after the last one arrives before mirroring
+/// them into the workspace, so a burst of saves (editor swap files, a `cargo fmt`, etc.) resyncs
+/// once instead of thrashing on every individual event
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
 
 pub struct LocalRun {
     pub sandbox: LocalSandbox,
+    source_path: PathBuf,
     _workspace: tempfile::TempDir,
 }
 
+impl LocalRun {
+    /// starts observing `source_path` for edits made while the agent is running and mirrors them
+    /// into this run's workspace, re-evaluating the same `exclude` patterns and `respect_gitignore`
+    /// setting `setup_local_sandbox` used per changed path. Best-effort like
+    /// [`crate::watch::ConfigWatcher`]: the caller decides whether a setup failure here is fatal
+    /// (it isn't — the run just falls back to the one-time snapshot already in place), and once
+    /// running, [`SourceWatcher::poll`] never fails the run either, falling back to a full resync
+    /// instead.
+    pub fn watch(&self, exclude: &[String], respect_gitignore: bool) -> Result<SourceWatcher> {
+        let matcher = build_exclude_matcher(exclude)?;
+        SourceWatcher::new(
+            self.source_path.clone(),
+            self.sandbox.workdir.clone(),
+            matcher,
+            respect_gitignore,
+        )
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct LocalSandbox {
     root: PathBuf,
     workdir: PathBuf,
     env: HashMap<String, String>,
+    isolation: Option<isolation::IsolationOptions>,
 }
 
 impl LocalSandbox {
@@ -32,18 +60,33 @@ impl LocalSandbox {
     }
 }
 
-impl Sandbox for LocalSandbox {
-    async fn exec(&mut self, command: &str) -> Result<ExecResult> {
+impl LocalSandbox {
+    /// build the `sh -c <command>` invocation shared by `exec`/`exec_streaming`: workdir, env,
+    /// its own process group (so `kill_on_drop` takes the whole child tree), and isolation.
+    fn build_command(&self, command: &str) -> Command {
         let mut cmd = Command::new("sh");
         cmd.arg("-c").arg(command).current_dir(&self.workdir);
         for (k, v) in &self.env {
             cmd.env(k, v);
         }
-        // own process group so kill_on_drop takes the whole child tree
         cmd.process_group(0);
         cmd.kill_on_drop(true);
 
-        let child = cmd.spawn()?;
+        if let Some(options) = self.isolation.clone() {
+            let root = self.root.clone();
+            // SAFETY: `isolation::enter` only calls async-signal-safe raw syscalls (unshare,
+            // mount, fork+waitpid, prctl) between fork and exec, as `pre_exec` requires.
+            unsafe {
+                cmd.pre_exec(move || isolation::enter(&root, &options));
+            }
+        }
+        cmd
+    }
+}
+
+impl Sandbox for LocalSandbox {
+    async fn exec(&mut self, command: &str) -> Result<ExecResult> {
+        let child = self.build_command(command).spawn()?;
         let output = child.wait_with_output().await?;
         Ok(ExecResult {
             exit_code: output.status.code().unwrap_or(-1) as isize,
@@ -52,6 +95,46 @@ impl Sandbox for LocalSandbox {
         })
     }
 
+    /// runs `command` under an optional `timeout`, genuinely cancelling (and, thanks to
+    /// `kill_on_drop` + its own process group) killing the whole child tree if it elapses. Like
+    /// `DaggerSandbox::exec_streaming`, `on_chunk` is only replayed once the command finishes —
+    /// there is no incremental pipe reader here either — so this buys a real per-step deadline,
+    /// not truly live output.
+    async fn exec_streaming(
+        &mut self,
+        command: &str,
+        on_chunk: &mut dyn FnMut(ShellChunk),
+        timeout: Option<Duration>,
+    ) -> Result<ExecResult> {
+        let child = self.build_command(command).spawn()?;
+        let wait = child.wait_with_output();
+        let output = match timeout {
+            Some(duration) => tokio::time::timeout(duration, wait)
+                .await
+                .map_err(|_| eyre::eyre!("command timed out after {duration:?}: {command}"))??,
+            None => wait.await?,
+        };
+
+        for line in output.stdout.split(|&b| b == b'\n') {
+            if !line.is_empty() {
+                on_chunk(ShellChunk::Stdout(line.to_vec()));
+            }
+        }
+        for line in output.stderr.split(|&b| b == b'\n') {
+            if !line.is_empty() {
+                on_chunk(ShellChunk::Stderr(line.to_vec()));
+            }
+        }
+        let exit_code = output.status.code().unwrap_or(-1) as isize;
+        on_chunk(ShellChunk::Exited(exit_code));
+
+        Ok(ExecResult {
+            exit_code,
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+
     async fn write_file(&mut self, path: &str, content: &str) -> Result<()> {
         let target = self.resolve_path(path)?;
         if let Some(parent) = target.parent() {
@@ -117,6 +200,48 @@ impl Sandbox for LocalSandbox {
         copy_tree(&source, &target)?;
         Ok(target.to_string_lossy().to_string())
     }
+
+    async fn export_archive(
+        &self,
+        container_path: &str,
+        host_path: &str,
+        compression_level: Option<u32>,
+    ) -> Result<String> {
+        let source = self.resolve_path(container_path)?;
+        let target = PathBuf::from(host_path);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let level = flate2::Compression::new(compression_level.unwrap_or_else(|| flate2::Compression::default().level()));
+        let file = std::fs::File::create(&target)?;
+        let encoder = flate2::write::GzEncoder::new(file, level);
+        let mut builder = tar::Builder::new(encoder);
+        append_tar_entries(&mut builder, &source, &source)?;
+        builder.into_inner()?.finish()?;
+
+        Ok(target.to_string_lossy().to_string())
+    }
+
+    async fn refresh_from_host(&mut self, host_path: &str, container_path: &str) -> Result<()> {
+        let source = PathBuf::from(host_path);
+        let target = self.resolve_path(container_path)?;
+        if source.is_file() {
+            if let Some(parent) = target.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::copy(&source, &target).await?;
+        } else {
+            std::fs::create_dir_all(&target)?;
+            copy_tree(&source, &target)?;
+        }
+        Ok(())
+    }
+
+    async fn set_env(&mut self, key: &str, value: &str) -> Result<()> {
+        self.env.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
 }
 
 pub fn setup_local_sandbox(
@@ -124,6 +249,8 @@ pub fn setup_local_sandbox(
     config: &ForgeConfig,
     source_path: &Path,
     config_dir: &Path,
+    secrets: &HashMap<String, String>,
+    clean_env: bool,
 ) -> Result<LocalRun> {
     let workspace = tempfile::tempdir()?;
     let root = workspace.path().to_path_buf();
@@ -133,7 +260,7 @@ pub fn setup_local_sandbox(
     std::fs::create_dir_all(&workdir)?;
 
     let matcher = build_exclude_matcher(&config.project.exclude)?;
-    copy_dir_with_excludes(source_path, &workdir, source_path, &matcher)?;
+    copy_dir_with_excludes(source_path, &workdir, &matcher, config.project.respect_gitignore)?;
 
     for mount in &config.mounts {
         let host_path = mount.resolve_host_path(config_dir)?;
@@ -159,7 +286,13 @@ pub fn setup_local_sandbox(
     if !config.container.setup.is_empty() || !config.container.user_setup.is_empty() {
         warn!("local runtime ignores [container] setup/user directives");
     }
-    if !config.container.env.is_empty() {
+    let literal_env_count = config
+        .container
+        .env
+        .values()
+        .filter(|v| !is_secret_marker(v))
+        .count();
+    if literal_env_count > 0 {
         warn!("local runtime ignores [container.env]; use host environment variables instead");
     }
 
@@ -167,17 +300,313 @@ pub fn setup_local_sandbox(
     if let Some(key) = &auth.api_key {
         env.insert("ANTHROPIC_API_KEY".to_string(), key.clone());
     }
+    // secrets are resolved from the host environment regardless of runtime, so they still apply
+    env.extend(secrets.clone());
+    normalize_environment(&mut env, clean_env);
 
     if matches!(config.agent.backend, AgentBackend::OpenCode) {
         info!("using host OpenCode auth/config in local runtime");
     }
 
+    let isolation = resolve_isolation(&config.project.isolation)?;
+
     Ok(LocalRun {
-        sandbox: LocalSandbox { root, workdir, env },
+        sandbox: LocalSandbox { root, workdir, env, isolation },
+        source_path: source_path.to_path_buf(),
         _workspace: workspace,
     })
 }
 
+/// turns the declarative `project.isolation` config into ready-to-apply
+/// [`isolation::IsolationOptions`], or `None` if isolation is off or unsupported on this host.
+/// Never fails the run over unsupported isolation — that's the whole point of it being
+/// best-effort — but does fail if `enabled = true` names a `readonly_paths` entry that doesn't
+/// exist, since that's a config mistake the user should hear about up front rather than at
+/// exec time inside a namespace.
+fn resolve_isolation(config: &IsolationConfig) -> Result<Option<isolation::IsolationOptions>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    if !isolation::is_supported() {
+        warn!(
+            "project.isolation.enabled is set but this host doesn't support rootless namespace \
+             isolation (needs Linux/x86_64 with unprivileged user namespaces); running unconfined"
+        );
+        return Ok(None);
+    }
+
+    let mut readonly_paths = Vec::with_capacity(config.readonly_paths.len());
+    for path in &config.readonly_paths {
+        let path = if let Some(rest) = path.strip_prefix('~') {
+            let home = std::env::var("HOME")
+                .map_err(|_| eyre::eyre!("HOME not set, cannot expand ~ in isolation.readonly_paths"))?;
+            PathBuf::from(format!("{home}{rest}"))
+        } else {
+            PathBuf::from(path)
+        };
+        if !path.exists() {
+            bail!("project.isolation.readonly_paths entry '{}' does not exist", path.display());
+        }
+        readonly_paths.push(path);
+    }
+
+    Ok(Some(isolation::IsolationOptions {
+        network: config.network,
+        readonly_paths,
+    }))
+}
+
+/// colon-delimited environment variables a packaging wrapper (Flatpak/Snap/AppImage) commonly
+/// rewrites to point at its own bundled runtime instead of the host's
+const PATHLIST_VARS: &[&str] = &[
+    "PATH",
+    "LD_LIBRARY_PATH",
+    "GST_PLUGIN_PATH",
+    "GST_PLUGIN_SYSTEM_PATH",
+    "XDG_DATA_DIRS",
+    "XDG_CONFIG_DIRS",
+];
+
+/// host-path prefixes that belong to whichever packaging wrapper (if any) `agent-build` is
+/// currently running inside, to be stripped out of `PATHLIST_VARS` before they reach the shell
+/// commands the agent spawns
+fn wrapper_prefixes() -> Vec<String> {
+    let mut prefixes = Vec::new();
+    if let Some(appdir) = std::env::var_os("APPIMAGE").and(std::env::var_os("APPDIR")) {
+        prefixes.push(appdir.to_string_lossy().into_owned());
+    }
+    if std::env::var_os("SNAP").is_some() {
+        prefixes.push("/snap/".to_string());
+    }
+    if Path::new("/.flatpak-info").is_file() {
+        prefixes.push("/app/".to_string());
+    }
+    prefixes
+}
+
+/// true if any of the wrapper markers `wrapper_prefixes` looks for (`APPIMAGE`, `SNAP`, or a
+/// readable `/.flatpak-info`) is present
+fn running_in_packaging_wrapper() -> bool {
+    std::env::var_os("APPIMAGE").is_some()
+        || std::env::var_os("SNAP").is_some()
+        || Path::new("/.flatpak-info").is_file()
+}
+
+/// splits `value` on `:`, drops empty segments and any segment starting with a `strip_prefixes`
+/// entry, then deduplicates while keeping the *last* occurrence of each remaining entry — so a
+/// genuine host entry re-added after the wrapper's own prefix wins over the earlier, injected one
+fn normalize_pathlist(value: &str, strip_prefixes: &[String]) -> String {
+    let mut seen = std::collections::HashSet::new();
+    let mut kept: Vec<&str> = Vec::new();
+    for segment in value.split(':').rev() {
+        if segment.is_empty() {
+            continue;
+        }
+        if strip_prefixes.iter().any(|p| segment.starts_with(p.as_str())) {
+            continue;
+        }
+        if seen.insert(segment) {
+            kept.push(segment);
+        }
+    }
+    kept.reverse();
+    kept.join(":")
+}
+
+/// rewrites `env`'s `PATHLIST_VARS` entries (read from the host process, since `env` itself only
+/// holds overrides at this point) to strip wrapper-injected prefixes, drop empty segments, and
+/// deduplicate. Runs automatically when a Flatpak/Snap/AppImage wrapper is detected, or always
+/// when `force` is set; otherwise a no-op, since a normal host environment doesn't need it.
+fn normalize_environment(env: &mut HashMap<String, String>, force: bool) {
+    if !force && !running_in_packaging_wrapper() {
+        return;
+    }
+    let strip_prefixes = wrapper_prefixes();
+    for var in PATHLIST_VARS {
+        let Some(value) = std::env::var_os(var) else {
+            continue;
+        };
+        let value = value.to_string_lossy();
+        let normalized = normalize_pathlist(&value, &strip_prefixes);
+        if normalized != *value {
+            info!(var = %var, before = %value, after = %normalized, "normalized environment list for local runtime");
+        }
+        env.insert((*var).to_string(), normalized);
+    }
+}
+
+/// incrementally mirrors edits under `source_path` into `workdir`, so a long-running local
+/// sandbox keeps seeing the file tree a developer is actively editing instead of only the
+/// one-time snapshot `copy_dir_with_excludes` took at setup. Backed by a `notify` filesystem
+/// watcher running on its own thread; [`SourceWatcher::poll`] is the non-blocking, best-effort
+/// side the rest of the pipeline calls into.
+pub struct SourceWatcher {
+    rx: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+    // kept alive only so the OS watch isn't torn down; never read directly
+    _watcher: notify::RecommendedWatcher,
+    source_path: PathBuf,
+    workdir: PathBuf,
+    matcher: GlobSet,
+    respect_gitignore: bool,
+    pending: HashSet<PathBuf>,
+    last_event: Option<Instant>,
+}
+
+impl SourceWatcher {
+    fn new(
+        source_path: PathBuf,
+        workdir: PathBuf,
+        matcher: GlobSet,
+        respect_gitignore: bool,
+    ) -> Result<Self> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })?;
+        notify::Watcher::watch(&mut watcher, &source_path, notify::RecursiveMode::Recursive)?;
+        info!(source = %source_path.display(), "watching source tree for incremental resync");
+        Ok(Self {
+            rx,
+            _watcher: watcher,
+            source_path,
+            workdir,
+            matcher,
+            respect_gitignore,
+            pending: HashSet::new(),
+            last_event: None,
+        })
+    }
+
+    /// drains any buffered filesystem events without blocking and, once `WATCH_DEBOUNCE` has
+    /// passed since the last one arrived, mirrors the accumulated changes into the workspace.
+    /// Never blocks the caller on I/O that isn't ready yet, and never fails the run: an error
+    /// partway through an incremental sync, or an event the watcher itself reports as an error,
+    /// falls back to re-running `copy_dir_with_excludes` over the whole tree instead.
+    pub fn poll(&mut self) {
+        let mut saw_error = false;
+        loop {
+            match self.rx.try_recv() {
+                Ok(Ok(event)) => {
+                    self.last_event = Some(Instant::now());
+                    self.pending.extend(event.paths);
+                }
+                Ok(Err(e)) => {
+                    debug!(error = %e, "source watcher reported an error, falling back to full resync");
+                    saw_error = true;
+                    self.last_event = Some(Instant::now());
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    debug!("source watcher channel disconnected, falling back to full resync");
+                    saw_error = true;
+                    self.last_event = Some(Instant::now());
+                    break;
+                }
+            }
+        }
+
+        let Some(last_event) = self.last_event else {
+            return;
+        };
+        if last_event.elapsed() < WATCH_DEBOUNCE {
+            return;
+        }
+
+        let paths = std::mem::take(&mut self.pending);
+        self.last_event = None;
+
+        if saw_error || self.sync_paths(&paths).is_err() {
+            if let Err(e) = self.full_resync() {
+                warn!(error = %e, "full source resync failed; workspace may be stale until the next change");
+            }
+        }
+    }
+
+    fn sync_paths(&self, paths: &HashSet<PathBuf>) -> Result<()> {
+        for path in paths {
+            let rel = path
+                .strip_prefix(&self.source_path)
+                .map_err(|_| eyre::eyre!("watched path '{}' is outside the source root", path.display()))?;
+            if rel.as_os_str().is_empty() {
+                bail!("ambiguous change at the source root");
+            }
+            let rel_norm = rel.to_string_lossy().replace('\\', "/");
+            let dest = self.workdir.join(rel);
+            if self.matcher.is_match(&rel_norm)
+                || (self.respect_gitignore && is_gitignored(path, &self.source_path))
+            {
+                remove_path(&dest)?;
+                continue;
+            }
+            match std::fs::symlink_metadata(path) {
+                Ok(meta) if meta.is_dir() => std::fs::create_dir_all(&dest)?,
+                Ok(_) => {
+                    if let Some(parent) = dest.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::copy(path, &dest)?;
+                }
+                // the path no longer exists on the host: mirror the deletion
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => remove_path(&dest)?,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    fn full_resync(&self) -> Result<()> {
+        debug!(workdir = %self.workdir.display(), "running full source resync");
+        if self.workdir.exists() {
+            std::fs::remove_dir_all(&self.workdir)?;
+        }
+        copy_dir_with_excludes(
+            &self.source_path,
+            &self.workdir,
+            &self.matcher,
+            self.respect_gitignore,
+        )
+    }
+}
+
+fn remove_path(path: &Path) -> Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => std::fs::remove_dir_all(path)?,
+        Ok(_) => std::fs::remove_file(path)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    Ok(())
+}
+
+/// true if `path` is covered by a `.gitignore` found anywhere between `root` and `path`'s parent
+/// directory. Used for the incremental side of [`SourceWatcher`], where re-walking the whole tree
+/// per event would defeat the point of syncing only the changed paths; `copy_dir_with_excludes`
+/// uses `ignore::WalkBuilder` directly instead since it's already walking the full tree.
+fn is_gitignored(path: &Path, root: &Path) -> bool {
+    let mut ancestors = Vec::new();
+    let mut dir = path.parent();
+    while let Some(d) = dir {
+        ancestors.push(d.to_path_buf());
+        if d == root {
+            break;
+        }
+        dir = d.parent();
+    }
+
+    let mut builder = ignore::gitignore::GitignoreBuilder::new(root);
+    for dir in ancestors.into_iter().rev() {
+        let candidate = dir.join(".gitignore");
+        if candidate.is_file() {
+            let _ = builder.add(candidate);
+        }
+    }
+    let Ok(gitignore) = builder.build() else {
+        return false;
+    };
+    let is_dir = std::fs::symlink_metadata(path).map(|m| m.is_dir()).unwrap_or(false);
+    gitignore.matched(path, is_dir).is_ignore()
+}
+
 fn resolve_local_mount_target(
     mount: &MountConfig,
     workdir_rel: &Path,
@@ -230,30 +659,60 @@ fn build_exclude_matcher(patterns: &[String]) -> Result<GlobSet> {
     Ok(builder.build()?)
 }
 
+/// mirrors `source` into `target`, skipping anything matched by `matcher` (the user's configured
+/// `project.exclude` globs — always applied, as the base layer) and, when `respect_gitignore` is
+/// set, anything ignored by `.gitignore`/`.ignore` files found anywhere in the tree. Matched
+/// directories are pruned rather than just skipped, so excluding e.g. `target/` doesn't pay the
+/// cost of walking a large build directory just to discard it.
 fn copy_dir_with_excludes(
     source: &Path,
     target: &Path,
-    source_root: &Path,
     matcher: &GlobSet,
+    respect_gitignore: bool,
 ) -> Result<()> {
     std::fs::create_dir_all(target)?;
-    for entry in std::fs::read_dir(source)? {
+
+    let root = source.to_path_buf();
+    let matcher_for_filter = matcher.clone();
+    let mut builder = ignore::WalkBuilder::new(source);
+    builder
+        .hidden(false)
+        .git_ignore(respect_gitignore)
+        .git_global(respect_gitignore)
+        .git_exclude(respect_gitignore)
+        .ignore(respect_gitignore)
+        .parents(respect_gitignore)
+        .require_git(false)
+        .filter_entry(move |entry| {
+            let Ok(rel) = entry.path().strip_prefix(&root) else {
+                return true;
+            };
+            if rel.as_os_str().is_empty() {
+                return true;
+            }
+            let rel_norm = rel.to_string_lossy().replace('\\', "/");
+            !matcher_for_filter.is_match(&rel_norm)
+        });
+
+    for entry in builder.build() {
         let entry = entry?;
         let src_path = entry.path();
-        let rel = src_path.strip_prefix(source_root)?;
-        let rel_norm = rel.to_string_lossy().replace('\\', "/");
-        if matcher.is_match(&rel_norm) {
+        if src_path == source {
             continue;
         }
 
-        let dst_path = target.join(entry.file_name());
-        if entry.file_type()?.is_dir() {
-            copy_dir_with_excludes(&src_path, &dst_path, source_root, matcher)?;
-        } else if entry.file_type()?.is_file() {
+        let rel = src_path.strip_prefix(source)?;
+        let dst_path = target.join(rel);
+        let file_type = entry
+            .file_type()
+            .ok_or_else(|| eyre::eyre!("unknown file type for '{}'", src_path.display()))?;
+        if file_type.is_dir() {
+            std::fs::create_dir_all(&dst_path)?;
+        } else if file_type.is_file() {
             if let Some(parent) = dst_path.parent() {
                 std::fs::create_dir_all(parent)?;
             }
-            std::fs::copy(&src_path, &dst_path)?;
+            std::fs::copy(src_path, &dst_path)?;
         }
     }
     Ok(())
@@ -285,6 +744,36 @@ fn copy_tree(source: &Path, target: &Path) -> Result<()> {
     Ok(())
 }
 
+/// walks `dir` and appends every file/directory under it to `builder`, with paths written
+/// relative to `root` and each entry's mode/mtime preserved from its host metadata. Mirrors
+/// `copy_tree`'s dir/file distinction — anything that's neither (a symlink) is skipped.
+fn append_tar_entries<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    dir: &Path,
+    root: &Path,
+) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let rel = path.strip_prefix(root)?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            let mut header = tar::Header::new_gnu();
+            header.set_metadata(&entry.metadata()?);
+            header.set_size(0);
+            header.set_cksum();
+            builder.append_data(&mut header, rel, std::io::empty())?;
+            append_tar_entries(builder, &path, root)?;
+        } else if file_type.is_file() {
+            let mut header = tar::Header::new_gnu();
+            header.set_metadata(&entry.metadata()?);
+            header.set_cksum();
+            builder.append_data(&mut header, rel, std::fs::File::open(&path)?)?;
+        }
+    }
+    Ok(())
+}
+
 fn normalize_workdir(path: &str) -> Result<PathBuf> {
     if path.is_empty() {
         bail!("path must not be empty");
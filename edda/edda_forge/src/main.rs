@@ -1,19 +1,35 @@
 mod config;
 mod container;
+mod credentials;
+mod diff;
+mod events;
+mod goodfile;
+mod isolation;
 mod local;
+mod publish;
+mod report;
 mod runner;
+mod source;
 mod state;
+mod tui;
+mod watch;
 
 use clap::{Parser, ValueEnum};
-use config::ForgeConfig;
+use config::{ForgeConfig, ForgeOverride};
+use credentials::EnvVarsExt;
+use edda_mcp::env::EnvVars;
 use edda_sandbox::Sandbox;
 use edda_sandbox::dagger::{ConnectOpts, Logger};
+use events::{EventSink, ForgeEvent, JsonLinesEventSink, NullEventSink};
 use eyre::{Result, bail};
+use globset::GlobSetBuilder;
 use state::State;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::time::Instant;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
+use watch::ConfigWatcher;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
 enum RuntimeBackend {
@@ -67,9 +83,19 @@ EXAMPLES:
   # use a custom config and source directory
   edda-forge --prompt 'add input validation' --config ./forge.toml --source ./my-project
 
+  # clone a remote repo (with submodules) as the source, pinned to a ref
+  edda-forge --prompt 'add input validation' --source https://github.com/acme/widget.git --source-ref v2.1
+
   # export the full project directory instead of a patch
   edda-forge --prompt 'implement a REST API' --export-dir --output ./generated-app
 
+  # push a branch and open a pull request instead of writing a patch (requires a [forge]
+  # block in the config)
+  edda-forge --prompt 'fix the flaky retry test' --publish-pr
+
+  # write a git-format-patch series with a cover letter, delivered to a maildir inbox
+  edda-forge --prompt 'fix the flaky retry test' --format-patch --output ./series --inbox ~/Maildir
+
   # allow more retries for flaky validation steps
   edda-forge --prompt 'add benchmarks' --max-retries 5"
 )]
@@ -89,17 +115,28 @@ struct Cli {
     #[arg(long, value_name = "PATH")]
     config: Option<PathBuf>,
 
-    /// Path to source directory to copy into the runtime workspace
+    /// Path to source directory to copy into the runtime workspace, or a git/https URL to clone
+    ///
+    /// A URL (scheme prefix, scp-like `user@host:path` shorthand, or `.git` suffix) is cloned
+    /// into a scratch directory, with submodules initialized recursively, before the baseline
+    /// commit is made. If omitted and a config is found, resolves from config's project.source
+    /// field. If omitted and no config is found, uses the embedded Rust template.
+    #[arg(long, value_name = "DIR_OR_URL")]
+    source: Option<String>,
+
+    /// Ref (branch, tag, or commit) to check out after cloning a `--source` git URL
     ///
-    /// If omitted and a config is found, resolves from config's project.source field.
-    /// If omitted and no config is found, uses the embedded Rust template.
-    #[arg(long, value_name = "DIR")]
-    source: Option<PathBuf>,
+    /// Ignored unless `--source` is a git URL.
+    #[arg(long, value_name = "REF")]
+    source_ref: Option<String>,
 
     /// Output path for the result
     ///
-    /// Without --export-dir: writes a .patch file (extension added automatically).
-    /// With --export-dir: exports the full project directory to this path.
+    /// Without --export-dir/--publish-pr/--format-patch: writes a .patch file (extension added
+    /// automatically). With --export-dir: exports the full project directory to this path. With
+    /// --format-patch: treated as a directory and filled with a git-format-patch series plus a
+    /// cover letter. With --publish-pr: ignored for the result itself, but still used as the
+    /// base directory for collected `[artifacts]`.
     #[arg(long, default_value = "./forge-output", value_name = "PATH")]
     output: PathBuf,
 
@@ -107,25 +144,157 @@ struct Cli {
     #[arg(long, default_value_t = 3, value_name = "N")]
     max_retries: usize,
 
+    /// Abort the run once cumulative agent cost across plan/work/review exceeds this many
+    /// US dollars (as reported by the Claude backend's trajectory `total_cost_usd`)
+    #[arg(long, value_name = "USD")]
+    max_cost_usd: Option<f64>,
+
+    /// Abort the run once cumulative agent turns across plan/work/review exceeds this count
+    #[arg(long, value_name = "N")]
+    max_turns: Option<u32>,
+
     /// Runtime backend (`dagger` for containerized runs, `local` for host execution)
     #[arg(long, value_enum, default_value_t = RuntimeBackend::Local)]
     runtime: RuntimeBackend,
 
-    /// Export the full project directory instead of generating a .patch file
+    /// Normalize PATH/LD_LIBRARY_PATH/GST_PLUGIN_*/XDG_* before spawning shell commands in the
+    /// local runtime
+    ///
+    /// Always happens automatically when a Flatpak/Snap/AppImage wrapper is detected (its
+    /// mangled runtime paths otherwise break tools the agent shells out to); this flag forces
+    /// the same normalization even when no wrapper is detected. Ignored by `--runtime dagger`.
     #[arg(long)]
+    clean_env: bool,
+
+    /// Export the full project directory instead of generating a .patch file
+    #[arg(long, conflicts_with = "publish_pr")]
     export_dir: bool,
+
+    /// Push a branch and open a pull request against the config's `[forge]` block, instead of
+    /// writing a .patch file or exporting the project directory
+    #[arg(long, conflicts_with = "export_dir")]
+    publish_pr: bool,
+
+    /// Render a live TUI dashboard (state, tasks.md progress, validation status, logs) instead
+    /// of printing tracing output directly to the terminal
+    #[arg(long)]
+    tui: bool,
+
+    /// Write a git-format-patch series (one file per commit, plus a cover letter) instead of a
+    /// single squashed diff; consumable by `git am` and mailing-list-style review
+    #[arg(long, conflicts_with_all = ["export_dir", "publish_pr"])]
+    format_patch: bool,
+
+    /// Additionally deliver the generated patch series into a maildir-style folder
+    /// (new/cur/tmp) for mailing-list review tools
+    ///
+    /// Requires --format-patch.
+    #[arg(long, value_name = "DIR", requires = "format_patch")]
+    inbox: Option<PathBuf>,
+
+    /// Override agent.backend/model for this run (e.g. `claude`, `opencode:opencode/kimi-k2.5-free`)
+    #[arg(long, value_name = "BACKEND[:MODEL]")]
+    agent: Option<String>,
+
+    /// Override container.image for this run
+    #[arg(long, value_name = "IMAGE")]
+    image: Option<String>,
+
+    /// Override project.workdir for this run
+    #[arg(long = "workdir", value_name = "PATH")]
+    workdir_override: Option<String>,
+
+    /// Add/override a container env var for this run, may be repeated (KEY=VAL)
+    #[arg(long = "env", value_name = "KEY=VAL")]
+    env: Vec<String>,
+
+    /// Add a project.exclude glob pattern for this run, may be repeated
+    #[arg(long, value_name = "PATTERN")]
+    exclude: Vec<String>,
+
+    /// Append one JSON object per line (state transitions, exec results) to this file, so an
+    /// external orchestrator can monitor the run without scraping human-readable logs
+    #[arg(long, value_name = "PATH")]
+    event_log: Option<PathBuf>,
+
+    /// Write a versioned JSON run report (every agent trajectory event and validate result)
+    /// to this file once the run finishes, for CI diffing or POSTing to a results endpoint
+    #[arg(long, value_name = "PATH")]
+    report: Option<PathBuf>,
+
+    /// Print the Plan/Work/Review agent commands and every validate step's command instead of
+    /// running them, so you can preview token spend before committing to a real run
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Deterministically shuffle the order of independent (parallel) validation steps using
+    /// this seed, to shake out ordering-dependent bugs; omit to keep config file order. The
+    /// seed used is logged, so a run that shakes out a bug can be reproduced exactly.
+    #[arg(long, value_name = "SEED")]
+    shuffle_seed: Option<u64>,
+}
+
+impl Cli {
+    /// build the CLI-sourced config override from `--agent`/`--image`/`--workdir`/`--env`/`--exclude`
+    fn build_override(&self) -> Result<ForgeOverride> {
+        let agent = self
+            .agent
+            .as_deref()
+            .map(config::parse_agent_spec)
+            .transpose()
+            .map_err(|e| eyre::eyre!("--agent: {e}"))?;
+
+        let mut env = std::collections::HashMap::new();
+        for kv in &self.env {
+            let (key, value) = kv
+                .split_once('=')
+                .ok_or_else(|| eyre::eyre!("--env expects KEY=VAL, got: '{kv}'"))?;
+            env.insert(key.to_string(), value.to_string());
+        }
+
+        Ok(ForgeOverride {
+            agent,
+            image: self.image.clone(),
+            workdir: self.workdir_override.clone(),
+            env,
+            exclude: self.exclude.clone(),
+        })
+    }
 }
 
 #[tokio::main]
 async fn main() -> ExitCode {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("edda_forge=info")),
-        )
-        .init();
+    let cli = Cli::parse();
+
+    let env_filter = || {
+        tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("edda_forge=info"))
+    };
+
+    // in --tui mode, tracing output would otherwise corrupt the alternate screen: route it
+    // through the dashboard's log pane instead of stdout
+    let dashboard = if cli.tui {
+        let (handle, log_sink, join) = tui::start();
+        tracing_subscriber::fmt()
+            .with_env_filter(env_filter())
+            .with_writer(log_sink)
+            .with_ansi(false)
+            .init();
+        Some((handle, join))
+    } else {
+        tracing_subscriber::fmt().with_env_filter(env_filter()).init();
+        None
+    };
 
-    match run().await {
+    let tui_handle = dashboard.as_ref().map(|(handle, _)| handle.clone());
+    let result = run(cli, tui_handle).await;
+
+    if let Some((handle, join)) = dashboard {
+        handle.shutdown();
+        let _ = join.await;
+    }
+
+    match result {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) if is_interrupt(&e) => {
             info!("interrupted");
@@ -146,9 +315,7 @@ fn is_interrupt(err: &eyre::Report) -> bool {
     format!("{err:?}").contains("interrupted")
 }
 
-async fn run() -> Result<()> {
-    let cli = Cli::parse();
-
+async fn run(cli: Cli, tui: Option<tui::TuiHandle>) -> Result<()> {
     if cli.install_claude {
         return install_claude_command();
     }
@@ -176,16 +343,19 @@ async fn run() -> Result<()> {
             let candidates = cli
                 .source
                 .iter()
-                .map(|s| s.join("forge.toml"))
+                .filter(|s| !source::is_git_url(s))
+                .map(|s| PathBuf::from(s).join("forge.toml"))
                 .chain(std::iter::once(PathBuf::from("forge.toml")));
             candidates.into_iter().find(|p| p.exists())
         }
     };
 
-    let (forge_config, config_dir) = match &config_path {
+    let overrides = cli.build_override()?;
+
+    let (mut forge_config, config_dir) = match &config_path {
         Some(p) => {
             info!(config = %p.display(), "loading config");
-            let cfg = ForgeConfig::load(p)?;
+            let cfg = ForgeConfig::load_with_override(p, overrides.clone())?;
             let dir = p
                 .parent()
                 .unwrap_or(std::path::Path::new("."))
@@ -198,6 +368,16 @@ async fn run() -> Result<()> {
         }
     };
 
+    let env_vars = EnvVars::load()?;
+
+    if config_path.is_none() && !overrides.is_empty() {
+        overrides.clone().apply_to(&mut forge_config);
+        forge_config.validate(&env_vars)?;
+    }
+
+    // fail fast with a precise message instead of an opaque agent error mid-run
+    env_vars.validate_agent(&forge_config.agent)?;
+
     let agent_auth = match &forge_config.agent.backend {
         config::AgentBackend::Claude => {
             let api_key = std::env::var("ANTHROPIC_API_KEY").ok();
@@ -230,11 +410,10 @@ async fn run() -> Result<()> {
     };
 
     let source_path = match &cli.source {
-        Some(p) => {
-            if !p.exists() {
-                bail!("source path does not exist: {}", p.display());
-            }
-            p.clone()
+        Some(spec) => {
+            source::resolve_backend(spec, cli.source_ref.clone())
+                .resolve()
+                .await?
         }
         None if config_path.is_none() => {
             let manifest_dir = env!("CARGO_MANIFEST_DIR");
@@ -249,16 +428,61 @@ async fn run() -> Result<()> {
 
     info!(source = %source_path.display(), "resolved source path");
 
+    // an optional scripted validate/review pipeline ("goodfile"); ships as `forge.lua` next to
+    // `forge.toml` and, when present, replaces the TOML `steps.validate` list entirely
+    let goodfile_path = config_dir.join("forge.lua");
+    let goodfile_path = goodfile_path.exists().then_some(goodfile_path);
+    if let Some(path) = &goodfile_path {
+        info!(goodfile = %path.display(), "using scripted validation (forge.lua)");
+    }
+
+    // resolve `container.secrets`/`${secret:NAME}` references now so the values never
+    // touch the serialized config; `resolve_secrets` redacts nothing itself but callers
+    // must never log the returned map
+    let secrets = forge_config.container.resolve_secrets(&env_vars)?;
+
     let output = cli.output.clone();
     let max_retries = cli.max_retries;
+    let run_budget = runner::RunBudget {
+        max_cost_usd: cli.max_cost_usd,
+        max_turns: cli.max_turns,
+    };
+    let exec_mode = if cli.dry_run {
+        runner::ExecMode::DryRun
+    } else {
+        runner::ExecMode::Real
+    };
+    let shuffle_seed = cli.shuffle_seed;
+    if let Some(seed) = shuffle_seed {
+        info!(shuffle_seed = seed, "shuffling independent validation steps for this run");
+    }
     let export_dir = cli.export_dir;
+    let format_patch = cli.format_patch;
+    let inbox = cli.inbox.clone();
     let runtime = cli.runtime;
+    let clean_env = cli.clean_env;
+    let publisher = if cli.publish_pr {
+        let publish_config = forge_config
+            .forge
+            .as_ref()
+            .ok_or_else(|| eyre::eyre!("--publish-pr requires a [forge] block in the config"))?;
+        Some(publish::Publisher::new(publish_config, &env_vars)?)
+    } else {
+        None
+    };
+    let event_sink: Box<dyn EventSink> = match &cli.event_log {
+        Some(path) => Box::new(JsonLinesEventSink::create(path)?),
+        None => Box::new(NullEventSink),
+    };
+    let event_sink = event_sink.as_ref();
+    let report_path = cli.report.clone();
 
     match runtime {
         RuntimeBackend::Dagger => {
             // Claude installer + first cold container materialization can exceed the
             // default timeout, especially when multiple forge runs execute in parallel.
             let opts = ConnectOpts::new(Logger::Tracing, Some(3600));
+            let tui_ref = tui.as_ref();
             opts.connect(move |client| async move {
                 let mut sandbox = container::setup_container(
                     client,
@@ -266,15 +490,29 @@ async fn run() -> Result<()> {
                     &forge_config,
                     &source_path,
                     &config_dir,
+                    &secrets,
                 )
                 .await?;
+                let mut watcher = config_watcher(&config_path, &config_dir, &overrides, &forge_config);
                 run_pipeline(
                     &mut sandbox,
                     prompt,
                     output,
                     max_retries,
+                    run_budget,
+                    exec_mode,
+                    shuffle_seed,
                     export_dir,
+                    format_patch,
+                    inbox,
                     &forge_config,
+                    event_sink,
+                    report_path.as_deref(),
+                    watcher.as_mut(),
+                    goodfile_path.as_deref(),
+                    publisher.as_ref(),
+                    tui_ref,
+                    &secrets,
                 )
                 .await?;
 
@@ -295,15 +533,51 @@ async fn run() -> Result<()> {
         }
         RuntimeBackend::Local => {
             info!("using local runtime backend (no dagger)");
-            let mut run =
-                local::setup_local_sandbox(&agent_auth, &forge_config, &source_path, &config_dir)?;
+            let mut run = local::setup_local_sandbox(
+                &agent_auth,
+                &forge_config,
+                &source_path,
+                &config_dir,
+                &secrets,
+                clean_env,
+            )?;
+            match run.watch(
+                &forge_config.project.exclude,
+                forge_config.project.respect_gitignore,
+            ) {
+                Ok(mut source_watcher) => {
+                    tokio::spawn(async move {
+                        loop {
+                            tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+                            source_watcher.poll();
+                        }
+                    });
+                }
+                Err(e) => warn!(
+                    error = %e,
+                    "source watcher unavailable; edits made during this run won't be picked up"
+                ),
+            }
+            let mut watcher = config_watcher(&config_path, &config_dir, &overrides, &forge_config);
             run_pipeline(
                 &mut run.sandbox,
                 prompt,
                 output,
                 max_retries,
+                run_budget,
+                exec_mode,
+                shuffle_seed,
                 export_dir,
+                format_patch,
+                inbox,
                 &forge_config,
+                event_sink,
+                report_path.as_deref(),
+                watcher.as_mut(),
+                goodfile_path.as_deref(),
+                publisher.as_ref(),
+                tui.as_ref(),
+                &secrets,
             )
             .await?;
         }
@@ -312,23 +586,56 @@ async fn run() -> Result<()> {
     Ok(())
 }
 
+/// build a hot-reload watcher for the run, if the config was loaded from an on-disk file
+/// (nothing to watch for the embedded default config)
+fn config_watcher(
+    config_path: &Option<PathBuf>,
+    config_dir: &Path,
+    overrides: &ForgeOverride,
+    forge_config: &ForgeConfig,
+) -> Option<ConfigWatcher> {
+    let path = config_path.as_ref()?;
+    Some(ConfigWatcher::new(
+        path,
+        config_dir,
+        overrides.clone(),
+        forge_config.clone(),
+    ))
+}
+
 async fn run_pipeline(
     sandbox: &mut impl Sandbox,
     prompt: String,
     output: PathBuf,
     max_retries: usize,
+    run_budget: runner::RunBudget,
+    exec_mode: runner::ExecMode,
+    shuffle_seed: Option<u64>,
     export_dir: bool,
+    format_patch: bool,
+    inbox: Option<PathBuf>,
     forge_config: &ForgeConfig,
+    event_sink: &dyn EventSink,
+    report_path: Option<&Path>,
+    mut watcher: Option<&mut ConfigWatcher>,
+    goodfile: Option<&Path>,
+    publisher: Option<&publish::Publisher>,
+    tui: Option<&tui::TuiHandle>,
+    secrets: &HashMap<String, String>,
 ) -> Result<()> {
     // create git baseline for diff output
     info!("creating git baseline commit");
     let workdir = &forge_config.project.workdir;
+    // the `find` strips any nested `.git` left over from a submodule (normally already excluded
+    // on copy, but `project.exclude` can be overridden) so `git add -A` tracks submodule
+    // contents as plain files in the single flattened baseline, not gitlinks
     let git_init = sandbox
         .exec(
             "git init && \
              (git symbolic-ref HEAD refs/heads/main >/dev/null 2>&1 || true) && \
              git config user.email forge@local && \
              git config user.name forge && \
+             find . -mindepth 2 -name .git -prune -exec rm -rf {} + && \
              git add -A && git commit -m baseline --allow-empty",
         )
         .await?;
@@ -342,16 +649,31 @@ async fn run_pipeline(
     // clean up stale tasks.md from previous forge runs
     let _ = sandbox.exec("rm -f tasks.md").await;
 
+    let original_prompt = prompt.clone();
     let mut state = State::Init { prompt };
     let mut validate_retries = 0usize;
     let mut review_retries = 0usize;
+    let mut checkpoints = edda_sandbox::CheckpointStore::new();
+    let mut retry_tracker = state::RetryTracker::new(max_retries);
+    let mut metrics: HashMap<String, f64> = HashMap::new();
+    let mut run_metrics = runner::RunMetrics::default();
+    let report = report::RunReportCollector::new();
+    let mut review_feedback: Option<String> = None;
     let run_start = Instant::now();
 
     while !state.is_terminal() {
-        let old = format!("{state}");
+        if let Some(watcher) = watcher.as_deref_mut() {
+            if let Err(e) = watcher.poll_and_apply(sandbox).await {
+                warn!(error = %e, "failed to apply config hot-reload");
+            }
+        }
+
+        let old_state = state.clone();
+        let old = format!("{old_state}");
         let step_start = Instant::now();
 
-        // race each step against Ctrl+C so we exit promptly on interrupt
+        // race each step against Ctrl+C so we exit promptly on interrupt; in --tui mode raw
+        // mode suppresses SIGINT, so the dashboard's own key loop forwards it via `tui`'s abort
         let next = tokio::select! {
             s = step(
                 state,
@@ -360,30 +682,97 @@ async fn run_pipeline(
                 &mut review_retries,
                 max_retries,
                 forge_config,
+                &mut checkpoints,
+                &mut retry_tracker,
+                event_sink,
+                goodfile,
+                &mut metrics,
+                &run_budget,
+                &mut run_metrics,
+                exec_mode,
+                shuffle_seed,
+                &report,
+                &mut review_feedback,
+                tui,
+                secrets,
             ) => s,
             _ = tokio::signal::ctrl_c() => {
                 bail!("interrupted");
             }
+            _ = tui::TuiHandle::wait_for_abort(tui) => {
+                bail!("interrupted");
+            }
         };
         state = next;
 
+        if let Some(tui) = tui {
+            tui.emit(tui::TuiUpdate::State(format!("{state}")));
+        }
+
         info!(
             from = %old,
             to = %state,
             elapsed_secs = step_start.elapsed().as_secs(),
             "state transition"
         );
+
+        let retry_count = State::backtrack_edge(&old_state, &state)
+            .map(|edge| retry_tracker.count(edge))
+            .unwrap_or(0);
+        event_sink.emit(&ForgeEvent::Transition {
+            from: &old,
+            to: &format!("{state}"),
+            retry_count,
+        });
+    }
+    info!(
+        total_secs = run_start.elapsed().as_secs(),
+        cost_usd = run_metrics.cost_usd,
+        turns = run_metrics.turns,
+        "forge finished"
+    );
+    if let Some(path) = report_path {
+        report.report().write_to(path)?;
+        info!(path = %path.display(), "wrote run report");
     }
-    info!(total_secs = run_start.elapsed().as_secs(), "forge finished");
 
     match &state {
         State::Done => {
-            if export_dir {
+            if let Some(publisher) = publisher {
+                let branch = publish::branch_name(&original_prompt);
+                let title = format!("forge: {}", truncate_string(&original_prompt, 72));
+                info!(branch = %branch, "pushing branch for pull request");
+                publisher
+                    .push_branch(sandbox, workdir, &branch, &title)
+                    .await?;
+
+                let tasks_md = match runner::read_tasks(sandbox, workdir).await {
+                    Ok(tasks) => tasks,
+                    Err(e) => {
+                        warn!("could not read tasks.md for PR body: {e}");
+                        String::new()
+                    }
+                };
+                let body = publish::pr_body(&original_prompt, &tasks_md, review_feedback.as_deref());
+                let pr_url = publisher.open_pull_request(&title, &body, &branch).await?;
+                info!(pr = %pr_url, "pull request opened");
+            } else if export_dir {
                 info!(output = %output.display(), "exporting project directory");
                 sandbox
                     .export_directory(workdir, &output.to_string_lossy())
                     .await?;
                 info!("directory export complete");
+            } else if format_patch {
+                generate_format_patch(
+                    sandbox,
+                    forge_config,
+                    workdir,
+                    &original_prompt,
+                    review_feedback.as_deref(),
+                    &output,
+                    inbox.as_deref(),
+                )
+                .await?;
             } else {
                 let patch_path = if output.extension().is_some() {
                     output.clone()
@@ -412,6 +801,8 @@ async fn run_pipeline(
                 std::fs::write(&patch_path, &diff_result.stdout)?;
                 info!(patch = %patch_path.display(), "patch written");
             }
+
+            collect_artifacts(sandbox, forge_config, workdir, &output, &metrics).await?;
         }
         State::Failed { reason } => {
             error!(%reason, "forge failed");
@@ -423,6 +814,257 @@ async fn run_pipeline(
     Ok(())
 }
 
+/// a single file pulled out of the sandbox by [`collect_artifacts`], recorded in the manifest
+/// alongside its size on disk
+#[derive(serde::Serialize)]
+struct ArtifactEntry {
+    path: String,
+    bytes: u64,
+}
+
+/// the `artifacts/manifest.json` written next to the collected files: what was pulled out and
+/// the metrics recorded by `forge.lua` (if any) that justified the patch
+#[derive(serde::Serialize)]
+struct ArtifactManifest {
+    files: Vec<ArtifactEntry>,
+    metrics: HashMap<String, f64>,
+}
+
+/// pull files matching `config.artifacts.patterns` out of the sandbox's `workdir` into an
+/// `artifacts/` folder next to `output`, alongside a `manifest.json` recording each file's size
+/// and the metrics recorded during validation. A no-op when no patterns are configured.
+async fn collect_artifacts(
+    sandbox: &mut impl Sandbox,
+    config: &ForgeConfig,
+    workdir: &str,
+    output: &Path,
+    metrics: &HashMap<String, f64>,
+) -> Result<()> {
+    if config.artifacts.patterns.is_empty() {
+        return Ok(());
+    }
+
+    let staging = format!("{workdir}/.forge-artifacts");
+    sandbox
+        .exec(&format!("rm -rf '{staging}' && mkdir -p '{staging}'"))
+        .await?;
+
+    // match with the same `globset` crate `project.exclude` uses (so `**` behaves the same way
+    // throughout the config) rather than `find -path`, which doesn't understand `**` at all
+    let mut builder = GlobSetBuilder::new();
+    for pattern in &config.artifacts.patterns {
+        builder.add(globset::Glob::new(pattern)?);
+    }
+    let matcher = builder.build()?;
+
+    let listing = sandbox
+        .exec(&format!(
+            "cd '{workdir}' && find . -path './.forge-artifacts' -prune -o -type f -print"
+        ))
+        .await?;
+    if listing.exit_code != 0 {
+        warn!(stderr = %listing.stderr, "failed to list sandbox files, skipping artifact collection");
+        return Ok(());
+    }
+
+    let matches: Vec<&str> = listing
+        .stdout
+        .lines()
+        .map(|path| path.trim_start_matches("./"))
+        .filter(|path| matcher.is_match(path))
+        .collect();
+    // a pattern that matches nothing is not an error
+    if !matches.is_empty() {
+        let cmd = format!(
+            "cd '{workdir}' && printf '%s\\0' {paths} \
+             | xargs -0 -I{{}} sh -c 'mkdir -p \"{staging}/$(dirname \"{{}}\")\" && cp -a \"{{}}\" \"{staging}/{{}}\"'",
+            paths = matches
+                .iter()
+                .map(|p| format!("'{}'", p.replace('\'', "'\\''")))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+        let result = sandbox.exec(&cmd).await?;
+        if result.exit_code != 0 {
+            warn!(stderr = %result.stderr, "artifact collection failed, skipping");
+        }
+    }
+
+    let listing = sandbox
+        .exec(&format!("cd '{staging}' && find . -type f -printf '%s\\t%P\\n'"))
+        .await?;
+    if listing.exit_code != 0 {
+        warn!(stderr = %listing.stderr, "failed to list collected artifacts, skipping export");
+        return Ok(());
+    }
+
+    let files: Vec<ArtifactEntry> = listing
+        .stdout
+        .lines()
+        .filter_map(|line| {
+            let (size, path) = line.split_once('\t')?;
+            Some(ArtifactEntry {
+                path: path.to_string(),
+                bytes: size.parse().ok()?,
+            })
+        })
+        .collect();
+    if files.is_empty() {
+        info!("no artifacts matched the configured patterns");
+        return Ok(());
+    }
+
+    let artifacts_dir = output
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("artifacts");
+    info!(count = files.len(), dir = %artifacts_dir.display(), "exporting artifacts");
+    sandbox
+        .export_directory(&staging, &artifacts_dir.to_string_lossy())
+        .await?;
+
+    let manifest = ArtifactManifest {
+        files,
+        metrics: metrics.clone(),
+    };
+    std::fs::write(
+        artifacts_dir.join("manifest.json"),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+
+    Ok(())
+}
+
+/// writes a `git format-patch` series (a numbered commit plus a cover letter) for the pipeline's
+/// squashed changes, as an alternative to the single squashed diff — consumable by `git am` and
+/// mailing-list-style review tools. `output` is treated as a directory; if `inbox` is set, the
+/// same files are additionally delivered maildir-style.
+async fn generate_format_patch(
+    sandbox: &mut impl Sandbox,
+    forge_config: &ForgeConfig,
+    workdir: &str,
+    prompt: &str,
+    review_feedback: Option<&str>,
+    output: &Path,
+    inbox: Option<&Path>,
+) -> Result<()> {
+    let subject = truncate_string(prompt, 72);
+    let escaped_subject = subject.replace('\'', "'\\''");
+    let pathspec = forge_config.patch.git_diff_pathspec();
+
+    // commit onto the baseline so `git format-patch` has a real commit to render
+    let commit = sandbox
+        .exec(&format!(
+            "git add -A {pathspec} && git commit -m '{escaped_subject}' --allow-empty"
+        ))
+        .await?;
+    if commit.exit_code != 0 {
+        bail!("failed to commit changes for format-patch: {}", commit.stderr);
+    }
+
+    let series_dir = format!("{workdir}/.forge-format-patch");
+    let format = sandbox
+        .exec(&format!(
+            "rm -rf '{series_dir}' && mkdir -p '{series_dir}' && \
+             git format-patch --cover-letter -1 HEAD -o '{series_dir}'"
+        ))
+        .await?;
+    if format.exit_code != 0 {
+        bail!("git format-patch failed: {}", format.stderr);
+    }
+
+    let listing = sandbox.exec(&format!("ls '{series_dir}'")).await?;
+    if listing.exit_code != 0 {
+        bail!("failed to list generated patch series: {}", listing.stderr);
+    }
+
+    let tasks_md = match runner::read_tasks(sandbox, workdir).await {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            warn!("could not read tasks.md for cover letter: {e}");
+            String::new()
+        }
+    };
+    let blurb = format!(
+        "{prompt}\n\n## Tasks\n\n{tasks_md}\n\n## Review\n\n{}\n",
+        review_feedback.unwrap_or("(no review feedback recorded)")
+    );
+
+    std::fs::create_dir_all(output)?;
+
+    let mut delivered = Vec::new();
+    for name in listing.stdout.lines().filter(|l| !l.is_empty()) {
+        let path = format!("{series_dir}/{name}");
+        let mut content = sandbox.read_file(&path).await?;
+        if name.contains("cover-letter") {
+            // git format-patch's placeholder markers for --cover-letter, meant to be filled in
+            // programmatically or by hand before sending
+            content = content
+                .replace("*** SUBJECT HERE ***", &subject)
+                .replace("*** BLURB HERE ***", &blurb);
+        }
+        std::fs::write(output.join(name), &content)?;
+        delivered.push(content);
+    }
+    info!(dir = %output.display(), count = delivered.len(), "format-patch series written");
+
+    if let Some(inbox) = inbox {
+        deliver_maildir(inbox, &delivered)?;
+    }
+
+    Ok(())
+}
+
+/// delivers `patches` into a maildir-style `--inbox` folder's `new/` subdirectory (creating
+/// `new`/`cur`/`tmp` if missing). Each `git format-patch` file is already a valid RFC 2822
+/// message, so no reformatting is needed beyond a maildir-unique filename.
+fn deliver_maildir(inbox: &Path, patches: &[String]) -> Result<()> {
+    for sub in ["new", "cur", "tmp"] {
+        std::fs::create_dir_all(inbox.join(sub))?;
+    }
+    let pid = std::process::id();
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    for (index, content) in patches.iter().enumerate() {
+        let filename = format!("{ts}.{pid}_{index}.edda-forge:2,");
+        std::fs::write(inbox.join("new").join(filename), content)?;
+    }
+    info!(inbox = %inbox.display(), count = patches.len(), "delivered patch series to maildir inbox");
+    Ok(())
+}
+
+/// pushes `tasks.md` progress to the dashboard's task pane, done items first
+fn emit_task_stats(tui: Option<&tui::TuiHandle>, stats: &runner::TaskStats) {
+    if let Some(tui) = tui {
+        let mut lines: Vec<(bool, String)> =
+            stats.done_tasks.iter().map(|t| (true, t.clone())).collect();
+        lines.extend(stats.pending_tasks.iter().map(|t| (false, t.clone())));
+        tui.emit(tui::TuiUpdate::Tasks {
+            done: stats.done,
+            pending: stats.pending,
+            lines,
+        });
+    }
+}
+
+/// pushes the current validate/review retry counts to the dashboard's validation pane
+fn emit_retries(
+    tui: Option<&tui::TuiHandle>,
+    validate_retries: usize,
+    review_retries: usize,
+    max_retries: usize,
+) {
+    if let Some(tui) = tui {
+        tui.emit(tui::TuiUpdate::Retries {
+            validate_retries,
+            review_retries,
+            max_retries,
+        });
+    }
+}
+
 async fn step(
     state: State,
     sandbox: &mut impl Sandbox,
@@ -430,6 +1072,19 @@ async fn step(
     review_retries: &mut usize,
     max_retries: usize,
     config: &ForgeConfig,
+    checkpoints: &mut edda_sandbox::CheckpointStore,
+    retry_tracker: &mut state::RetryTracker,
+    event_sink: &dyn EventSink,
+    goodfile: Option<&Path>,
+    metrics: &mut HashMap<String, f64>,
+    run_budget: &runner::RunBudget,
+    run_metrics: &mut runner::RunMetrics,
+    exec_mode: runner::ExecMode,
+    shuffle_seed: Option<u64>,
+    report: &report::RunReportCollector,
+    review_feedback: &mut Option<String>,
+    tui: Option<&tui::TuiHandle>,
+    secrets: &HashMap<String, String>,
 ) -> State {
     let language = &config.project.language;
     let agent = &config.agent;
@@ -438,8 +1093,19 @@ async fn step(
     match state {
         State::Init { prompt } => State::Plan { prompt },
 
-        State::Plan { prompt } => match runner::plan(sandbox, agent, &prompt, language, workdir)
-            .await
+        State::Plan { prompt } => match runner::plan(
+            sandbox,
+            agent,
+            &prompt,
+            language,
+            workdir,
+            run_budget,
+            run_metrics,
+            report,
+            exec_mode,
+            secrets,
+        )
+        .await
         {
             Ok(()) => match runner::read_tasks(sandbox, workdir).await {
                 Ok(tasks) => {
@@ -449,6 +1115,7 @@ async fn step(
                             reason: "Plan produced no tasks (no `- [ ]` items in tasks.md)".into(),
                         };
                     }
+                    emit_task_stats(tui, &stats);
                     info!(tasks = stats.pending, "plan created");
                     for task in &stats.pending_tasks {
                         info!(task = %task, "planned");
@@ -468,8 +1135,16 @@ async fn step(
             let before = match runner::read_tasks(sandbox, workdir).await {
                 Ok(tasks) => {
                     let stats = runner::parse_task_stats(&tasks);
+                    emit_task_stats(tui, &stats);
                     if stats.pending == 0 {
                         info!(done = stats.done, "all tasks done, moving to validation");
+                        match sandbox.checkpoint("Work").await {
+                            Ok(id) => {
+                                checkpoints.record("Work", id);
+                                info!("checkpointed sandbox before validation");
+                            }
+                            Err(e) => debug!(error = %e, "sandbox does not support checkpoints, skipping"),
+                        }
                         return State::Validate { step_idx: 0 };
                     }
                     info!(
@@ -486,7 +1161,13 @@ async fn step(
                 }
             };
 
-            if let Err(e) = runner::work(sandbox, agent, language, workdir).await {
+            if let Err(e) =
+                runner::work(
+                    sandbox, agent, language, workdir, run_budget, run_metrics, report, exec_mode,
+                    secrets,
+                )
+                .await
+            {
                 return State::Failed {
                     reason: format!("Work: {e}"),
                 };
@@ -495,6 +1176,7 @@ async fn step(
             match runner::read_tasks(sandbox, workdir).await {
                 Ok(tasks) => {
                     let after = runner::parse_task_stats(&tasks);
+                    emit_task_stats(tui, &after);
                     if after.done <= before.done {
                         State::Failed {
                             reason: format!(
@@ -524,58 +1206,236 @@ async fn step(
         }
 
         State::Validate { step_idx } => {
-            let steps = &config.steps.validate;
+            if let Some(goodfile_path) = goodfile {
+                return match goodfile::evaluate(goodfile_path, sandbox) {
+                    Ok(verdict) => {
+                        for (name, value) in &verdict.metrics {
+                            debug!(metric = %name, value, "goodfile metric");
+                            metrics.insert(name.clone(), *value);
+                        }
+                        for artifact in &verdict.artifacts {
+                            debug!(artifact = %artifact, "goodfile artifact registered");
+                        }
+                        if verdict.passed {
+                            info!("goodfile validation passed");
+                            event_sink.emit(&ForgeEvent::Exec {
+                                step: "forge.lua",
+                                exit_code: 0,
+                                stdout_bytes: 0,
+                                stderr_bytes: 0,
+                            });
+                            if let Some(tui) = tui {
+                                tui.emit(tui::TuiUpdate::Step {
+                                    name: "forge.lua".to_string(),
+                                    status: tui::StepStatus::Passed,
+                                });
+                            }
+                            State::Review
+                        } else {
+                            let reason = verdict
+                                .reason
+                                .unwrap_or_else(|| "forge.lua reported failure".to_string());
+                            event_sink.emit(&ForgeEvent::Exec {
+                                step: "forge.lua",
+                                exit_code: 1,
+                                stdout_bytes: 0,
+                                stderr_bytes: reason.len(),
+                            });
+                            *validate_retries += 1;
+                            emit_retries(tui, *validate_retries, *review_retries, max_retries);
+                            if let Some(tui) = tui {
+                                tui.emit(tui::TuiUpdate::Step {
+                                    name: "forge.lua".to_string(),
+                                    status: tui::StepStatus::Failed,
+                                });
+                            }
+                            if *validate_retries > max_retries {
+                                return State::Failed {
+                                    reason: format!(
+                                        "goodfile validation failed after {max_retries} retries: {}",
+                                        truncate_string(&reason, 500)
+                                    ),
+                                };
+                            }
+                            warn!(
+                                attempt = *validate_retries,
+                                reason = %reason,
+                                "goodfile validation failed, appending fix task"
+                            );
+                            if retry_tracker.try_retry("Validate->Work") {
+                                if let Some(id) = checkpoints.latest("Work") {
+                                    match sandbox.restore(id).await {
+                                        Ok(()) => info!("restored sandbox to last checkpoint before retry"),
+                                        Err(e) => debug!(error = %e, "sandbox does not support restore, skipping"),
+                                    }
+                                }
+                            }
+                            let description = format!(
+                                "Fix: forge.lua validation failed (attempt {}) — {}",
+                                *validate_retries,
+                                truncate_string(&reason, 300)
+                            );
+                            match runner::append_task(sandbox, &description, workdir).await {
+                                Ok(()) => State::Work,
+                                Err(e) => State::Failed {
+                                    reason: format!("failed to append fix task: {e}"),
+                                },
+                            }
+                        }
+                    }
+                    Err(e) => State::Failed {
+                        reason: format!("forge.lua evaluation error: {e}"),
+                    },
+                };
+            }
+
+            let batches = runner::validate_batches(&config.steps.validate, shuffle_seed);
 
-            if step_idx >= steps.len() {
+            if step_idx == 0 {
+                if let Some(tui) = tui {
+                    for batch in &batches {
+                        for step in batch {
+                            tui.emit(tui::TuiUpdate::Step {
+                                name: step.name.clone(),
+                                status: tui::StepStatus::Pending,
+                            });
+                        }
+                    }
+                }
+            }
+
+            if step_idx >= batches.len() {
                 return State::Review;
             }
 
-            let step = &steps[step_idx];
+            let batch = &batches[step_idx];
 
-            match runner::run_validate_step(sandbox, step).await {
-                Ok(result) if result.exit_code == 0 => {
-                    info!(step = %step.name, "validation step passed");
-                    State::Validate {
-                        step_idx: step_idx + 1,
-                    }
+            if let Some(tui) = tui {
+                for step in batch {
+                    tui.emit(tui::TuiUpdate::Step {
+                        name: step.name.clone(),
+                        status: tui::StepStatus::Running,
+                    });
                 }
-                Ok(result) => {
-                    let error_output = format!("{}\n{}", result.stdout, result.stderr);
-                    *validate_retries += 1;
-                    if *validate_retries > max_retries {
+            }
+
+            let results =
+                runner::run_validate_batch(sandbox, batch, report, exec_mode, secrets).await;
+
+            let mut failures = Vec::new();
+            for runner::StepOutcome { name, outcome } in &results {
+                match outcome {
+                    Ok(runner::ValidateStatus::Passed { result }) => {
+                        info!(step = %name, "validation step passed");
+                        event_sink.emit(&ForgeEvent::Exec {
+                            step: name,
+                            exit_code: result.exit_code,
+                            stdout_bytes: result.stdout.len(),
+                            stderr_bytes: result.stderr.len(),
+                        });
+                        if let Some(tui) = tui {
+                            tui.emit(tui::TuiUpdate::Step {
+                                name: name.clone(),
+                                status: tui::StepStatus::Passed,
+                            });
+                        }
+                    }
+                    Ok(runner::ValidateStatus::Flaky { attempts, result }) => {
+                        warn!(step = %name, attempts, "validation step passed but was flaky");
+                        event_sink.emit(&ForgeEvent::Exec {
+                            step: name,
+                            exit_code: result.exit_code,
+                            stdout_bytes: result.stdout.len(),
+                            stderr_bytes: result.stderr.len(),
+                        });
+                        if let Some(tui) = tui {
+                            tui.emit(tui::TuiUpdate::Step {
+                                name: name.clone(),
+                                status: tui::StepStatus::Passed,
+                            });
+                        }
+                    }
+                    Ok(runner::ValidateStatus::Failed { result }) => {
+                        event_sink.emit(&ForgeEvent::Exec {
+                            step: name,
+                            exit_code: result.exit_code,
+                            stdout_bytes: result.stdout.len(),
+                            stderr_bytes: result.stderr.len(),
+                        });
+                        if let Some(tui) = tui {
+                            tui.emit(tui::TuiUpdate::Step {
+                                name: name.clone(),
+                                status: tui::StepStatus::Failed,
+                            });
+                        }
+                        failures.push(format!(
+                            "`{name}` failed:\n{}\n{}",
+                            result.stdout, result.stderr
+                        ));
+                    }
+                    Err(e) => {
+                        if let Some(tui) = tui {
+                            tui.emit(tui::TuiUpdate::Step {
+                                name: name.clone(),
+                                status: tui::StepStatus::Failed,
+                            });
+                        }
                         return State::Failed {
-                            reason: format!(
-                                "validation step '{}' failed after {} retries: {}",
-                                step.name,
-                                max_retries,
-                                truncate_string(&error_output, 500)
-                            ),
+                            reason: format!("validation step '{name}' exec error: {e}"),
                         };
                     }
+                }
+            }
 
-                    warn!(
-                        step = %step.name,
-                        attempt = *validate_retries,
-                        "validation failed, appending fix task"
-                    );
+            if failures.is_empty() {
+                return State::Validate {
+                    step_idx: step_idx + 1,
+                };
+            }
 
-                    let description = format!(
-                        "Fix: `{}` failed (attempt {}) — {}",
-                        step.name,
-                        *validate_retries,
-                        truncate_string(&error_output, 300)
-                    );
-                    match runner::append_task(sandbox, &description, workdir).await {
-                        Ok(()) => State::Work,
-                        Err(e) => State::Failed {
-                            reason: format!("failed to append fix task: {e}"),
-                        },
+            let error_output = failures.join("\n\n");
+            *validate_retries += 1;
+            emit_retries(tui, *validate_retries, *review_retries, max_retries);
+            if *validate_retries > max_retries {
+                return State::Failed {
+                    reason: format!(
+                        "validation failed after {} retries: {}",
+                        max_retries,
+                        truncate_string(&error_output, 500)
+                    ),
+                };
+            }
+
+            warn!(
+                failed = failures.len(),
+                attempt = *validate_retries,
+                "validation batch failed, appending fix tasks"
+            );
+
+            // roll back to the last known-good snapshot instead of letting the agent
+            // keep mutating the same drifting container
+            if retry_tracker.try_retry("Validate->Work") {
+                if let Some(id) = checkpoints.latest("Work") {
+                    match sandbox.restore(id).await {
+                        Ok(()) => info!("restored sandbox to last checkpoint before retry"),
+                        Err(e) => debug!(error = %e, "sandbox does not support restore, skipping"),
                     }
                 }
-                Err(e) => State::Failed {
-                    reason: format!("validation step '{}' exec error: {e}", step.name),
-                },
             }
+
+            for failure in &failures {
+                let description = format!(
+                    "Fix: validation failed (attempt {}) — {}",
+                    *validate_retries,
+                    truncate_string(failure, 300)
+                );
+                if let Err(e) = runner::append_task(sandbox, &description, workdir).await {
+                    return State::Failed {
+                        reason: format!("failed to append fix task: {e}"),
+                    };
+                }
+            }
+            State::Work
         }
 
         State::Review => {
@@ -585,15 +1445,23 @@ async fn step(
                 language,
                 workdir,
                 &config.patch.git_diff_pathspec(),
+                run_budget,
+                run_metrics,
+                report,
+                exec_mode,
+                secrets,
             )
             .await
             {
                 Ok(runner::ReviewVerdict::Approved) => {
                     info!("review approved");
+                    *review_feedback = Some("approved — no outstanding feedback".to_string());
                     State::Export
                 }
-                Ok(runner::ReviewVerdict::Rejected { feedback }) => {
+                Ok(runner::ReviewVerdict::Rejected { feedback, findings }) => {
+                    *review_feedback = Some(feedback.clone());
                     *review_retries += 1;
+                    emit_retries(tui, *validate_retries, *review_retries, max_retries);
                     if *review_retries > max_retries {
                         return State::Failed {
                             reason: format!(
@@ -607,22 +1475,37 @@ async fn step(
                     warn!(
                         attempt = *review_retries,
                         feedback = %feedback,
+                        findings = findings.len(),
                         "review rejected, appending fix task"
                     );
 
-                    let description = format!(
-                        "Fix: review rejected (attempt {}) — {}",
-                        *review_retries, feedback
-                    );
-                    match runner::append_task(sandbox, &description, workdir).await {
-                        Ok(()) => State::Work,
-                        Err(e) => State::Failed {
-                            reason: format!("failed to append fix task: {e}"),
-                        },
+                    if findings.is_empty() {
+                        let description = format!(
+                            "Fix: review rejected (attempt {}) — {}",
+                            *review_retries, feedback
+                        );
+                        match runner::append_task(sandbox, &description, workdir).await {
+                            Ok(()) => State::Work,
+                            Err(e) => State::Failed {
+                                reason: format!("failed to append fix task: {e}"),
+                            },
+                        }
+                    } else {
+                        for finding in &findings {
+                            let description =
+                                format!("Fix {}:{} — {}", finding.path, finding.line, finding.note);
+                            if let Err(e) = runner::append_task(sandbox, &description, workdir).await {
+                                return State::Failed {
+                                    reason: format!("failed to append fix task: {e}"),
+                                };
+                            }
+                        }
+                        State::Work
                     }
                 }
                 Ok(runner::ReviewVerdict::InvalidFormat) => {
                     *review_retries += 1;
+                    emit_retries(tui, *validate_retries, *review_retries, max_retries);
                     if *review_retries > max_retries {
                         return State::Failed {
                             reason: "review returned invalid format after max retries".into(),
@@ -0,0 +1,274 @@
+use crate::config::{ForgeKind, PublishConfig};
+use edda_mcp::env::EnvVars;
+use edda_sandbox::Sandbox;
+use eyre::{Result, bail};
+use serde::Deserialize;
+use serde_json::json;
+
+/// pushes a branch and opens a pull request against a git forge. Concrete implementations only
+/// need to supply credentials/repo identity and know how to call one forge's REST API to open a
+/// PR; [`push_branch`](ForgePublisher::push_branch) is shared since every forge here is pushed to
+/// over plain authenticated git.
+pub trait ForgePublisher {
+    fn base_url(&self) -> &str;
+    fn token(&self) -> &str;
+    fn repo(&self) -> &str;
+
+    /// commit whatever is staged in the sandbox's git repo onto a fresh `branch` off the
+    /// baseline commit, then push it to the remote
+    async fn push_branch(
+        &self,
+        sandbox: &mut impl Sandbox,
+        workdir: &str,
+        branch: &str,
+        title: &str,
+    ) -> Result<()> {
+        let remote = remote_url(self.base_url(), self.token(), self.repo());
+        let escaped_title = title.replace('\'', "'\\''");
+        let cmd = format!(
+            "cd '{workdir}' && git add -A && git checkout -b '{branch}' && \
+             git commit -m '{escaped_title}' --allow-empty && git push '{remote}' 'HEAD:{branch}'"
+        );
+        let result = sandbox.exec(&cmd).await?;
+        if result.exit_code != 0 {
+            // the token never appears in our own command construction above, but git's own
+            // error output sometimes echoes the remote url it tried to reach
+            bail!(
+                "failed to push branch '{branch}': {}",
+                result.stderr.replace(self.token(), "***")
+            );
+        }
+        Ok(())
+    }
+
+    /// open a PR from `branch` onto `base_branch`, returning its web URL
+    async fn open_pull_request(
+        &self,
+        title: &str,
+        body: &str,
+        branch: &str,
+        base_branch: &str,
+    ) -> Result<String>;
+}
+
+/// `scheme://x-access-token:<token>@host/repo.git`, the basic-auth-in-url form both Gitea and
+/// GitHub accept for pushing over https without a credential helper
+fn remote_url(base_url: &str, token: &str, repo: &str) -> String {
+    let scheme = if base_url.starts_with("http://") {
+        "http"
+    } else {
+        "https"
+    };
+    let host = base_url
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/');
+    format!("{scheme}://x-access-token:{token}@{host}/{repo}.git")
+}
+
+#[derive(Deserialize)]
+struct PullRequestResponse {
+    html_url: String,
+}
+
+pub struct GiteaPublisher {
+    base_url: String,
+    repo: String,
+    token: String,
+    base_branch: String,
+}
+
+impl ForgePublisher for GiteaPublisher {
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+    fn token(&self) -> &str {
+        &self.token
+    }
+    fn repo(&self) -> &str {
+        &self.repo
+    }
+
+    async fn open_pull_request(
+        &self,
+        title: &str,
+        body: &str,
+        branch: &str,
+        base_branch: &str,
+    ) -> Result<String> {
+        let (owner, repo) = self
+            .repo
+            .split_once('/')
+            .ok_or_else(|| eyre::eyre!("forge.repo must be 'owner/repo', got: '{}'", self.repo))?;
+        let url = format!(
+            "{}/api/v1/repos/{owner}/{repo}/pulls",
+            self.base_url.trim_end_matches('/')
+        );
+        let resp = reqwest::Client::new()
+            .post(&url)
+            .header("Authorization", format!("token {}", self.token))
+            .json(&json!({
+                "title": title,
+                "body": body,
+                "head": branch,
+                "base": base_branch,
+            }))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            bail!(
+                "gitea: failed to open pull request ({status}): {}",
+                resp.text().await.unwrap_or_default()
+            );
+        }
+        Ok(resp.json::<PullRequestResponse>().await?.html_url)
+    }
+}
+
+pub struct GitHubPublisher {
+    base_url: String,
+    repo: String,
+    token: String,
+    base_branch: String,
+}
+
+impl ForgePublisher for GitHubPublisher {
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+    fn token(&self) -> &str {
+        &self.token
+    }
+    fn repo(&self) -> &str {
+        &self.repo
+    }
+
+    async fn open_pull_request(
+        &self,
+        title: &str,
+        body: &str,
+        branch: &str,
+        base_branch: &str,
+    ) -> Result<String> {
+        let url = format!("{}/repos/{}/pulls", github_api_base(&self.base_url), self.repo);
+        let resp = reqwest::Client::new()
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "edda-forge")
+            .json(&json!({
+                "title": title,
+                "body": body,
+                "head": branch,
+                "base": base_branch,
+            }))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            bail!(
+                "github: failed to open pull request ({status}): {}",
+                resp.text().await.unwrap_or_default()
+            );
+        }
+        Ok(resp.json::<PullRequestResponse>().await?.html_url)
+    }
+}
+
+/// github.com's REST API lives on a different host than the web/git one; a GitHub Enterprise
+/// base_url instead gets the conventional `/api/v3` suffix
+fn github_api_base(base_url: &str) -> String {
+    let trimmed = base_url.trim_end_matches('/');
+    if trimmed == "https://github.com" {
+        "https://api.github.com".to_string()
+    } else {
+        format!("{trimmed}/api/v3")
+    }
+}
+
+/// picks the right [`ForgePublisher`] for `config.kind` and dispatches to it. A plain enum
+/// (rather than `dyn ForgePublisher`) since `ForgePublisher`'s methods are async and there are
+/// only ever two kinds to choose between.
+pub enum Publisher {
+    Gitea(GiteaPublisher),
+    GitHub(GitHubPublisher),
+}
+
+impl Publisher {
+    pub fn new(config: &PublishConfig, env: &EnvVars) -> Result<Self> {
+        let token = env
+            .get(&config.token_env)
+            .ok_or_else(|| eyre::eyre!("forge.token_env '{}' is not set", config.token_env))?
+            .to_string();
+        Ok(match config.kind {
+            ForgeKind::Gitea => Publisher::Gitea(GiteaPublisher {
+                base_url: config.base_url.clone(),
+                repo: config.repo.clone(),
+                token,
+                base_branch: config.base_branch.clone(),
+            }),
+            ForgeKind::GitHub => Publisher::GitHub(GitHubPublisher {
+                base_url: config.base_url.clone(),
+                repo: config.repo.clone(),
+                token,
+                base_branch: config.base_branch.clone(),
+            }),
+        })
+    }
+
+    pub async fn push_branch(
+        &self,
+        sandbox: &mut impl Sandbox,
+        workdir: &str,
+        branch: &str,
+        title: &str,
+    ) -> Result<()> {
+        match self {
+            Publisher::Gitea(p) => p.push_branch(sandbox, workdir, branch, title).await,
+            Publisher::GitHub(p) => p.push_branch(sandbox, workdir, branch, title).await,
+        }
+    }
+
+    pub async fn open_pull_request(&self, title: &str, body: &str, branch: &str) -> Result<String> {
+        match self {
+            Publisher::Gitea(p) => {
+                p.open_pull_request(title, body, branch, &p.base_branch).await
+            }
+            Publisher::GitHub(p) => {
+                p.open_pull_request(title, body, branch, &p.base_branch).await
+            }
+        }
+    }
+}
+
+/// a short, branch-safe slug from the first few words of `prompt`
+fn slugify(prompt: &str) -> String {
+    prompt
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .take(6)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// `forge/<slug>-<unix-secs>`, unique enough across runs without needing a counter
+pub fn branch_name(prompt: &str) -> String {
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format!("forge/{}-{ts}", slugify(prompt))
+}
+
+/// the PR body: what was asked for, what the agent's task list says it did, and the review
+/// verdict that approved the result
+pub fn pr_body(prompt: &str, tasks_md: &str, review_feedback: Option<&str>) -> String {
+    format!(
+        "## Prompt\n\n{prompt}\n\n## Tasks\n\n{tasks_md}\n\n## Review\n\n{}\n",
+        review_feedback.unwrap_or("(no review feedback recorded)")
+    )
+}
@@ -0,0 +1,133 @@
+use eyre::Result;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// schema version of the JSON run report, bumped whenever a breaking shape change is made so
+/// downstream tooling (CI diffing, a results endpoint) can detect an incompatible report
+const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// one event recorded against a step (`Plan`, `Work`, `Review`, or a validate step's name) over
+/// the course of a run; mirrors what `log_trajectory`/`run_validate_step` already log via
+/// `tracing`, but retained as data instead of being thrown away once the log line is emitted
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TrajectoryEvent {
+    AgentText {
+        step: String,
+        text: String,
+    },
+    ToolUse {
+        step: String,
+        name: String,
+        args: serde_json::Value,
+    },
+    ToolResult {
+        step: String,
+        result: String,
+    },
+    StepFinished {
+        step: String,
+        turns: u32,
+        cost_usd: f64,
+        is_error: bool,
+    },
+    ValidateResult {
+        step: String,
+        exit_code: isize,
+        duration_ms: u128,
+    },
+    ReviewVerdict {
+        approved: bool,
+        feedback: Option<String>,
+    },
+}
+
+/// collects `TrajectoryEvent`s across an entire plan -> work -> review -> validate run, for
+/// serialization into a versioned [`RunReport`] once the run finishes
+#[derive(Default)]
+pub struct RunReportCollector {
+    events: Mutex<Vec<TrajectoryEvent>>,
+}
+
+impl RunReportCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, event: TrajectoryEvent) {
+        if let Ok(mut events) = self.events.lock() {
+            events.push(event);
+        }
+    }
+
+    /// snapshot the events collected so far into a versioned report
+    pub fn report(&self) -> RunReport {
+        RunReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            events: self.events.lock().map(|e| e.clone()).unwrap_or_default(),
+        }
+    }
+}
+
+/// a versioned, schema'd document of everything that happened during one forge run, suitable
+/// for diffing across CI runs or POSTing to a results endpoint
+#[derive(Debug, Serialize)]
+pub struct RunReport {
+    pub schema_version: u32,
+    pub events: Vec<TrajectoryEvent>,
+}
+
+impl RunReport {
+    /// write the report as pretty-printed JSON to `path`
+    pub fn write_to(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collector_preserves_push_order() {
+        let collector = RunReportCollector::new();
+        collector.push(TrajectoryEvent::AgentText {
+            step: "Plan".to_string(),
+            text: "hello".to_string(),
+        });
+        collector.push(TrajectoryEvent::StepFinished {
+            step: "Plan".to_string(),
+            turns: 3,
+            cost_usd: 0.42,
+            is_error: false,
+        });
+
+        let report = collector.report();
+        assert_eq!(report.schema_version, REPORT_SCHEMA_VERSION);
+        assert_eq!(report.events.len(), 2);
+        assert!(matches!(report.events[0], TrajectoryEvent::AgentText { .. }));
+        assert!(matches!(report.events[1], TrajectoryEvent::StepFinished { .. }));
+    }
+
+    #[test]
+    fn test_write_to_produces_valid_json() {
+        let collector = RunReportCollector::new();
+        collector.push(TrajectoryEvent::ReviewVerdict {
+            approved: true,
+            feedback: None,
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+        collector.report().write_to(&path).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(parsed["schema_version"], REPORT_SCHEMA_VERSION);
+        assert_eq!(parsed["events"][0]["event"], "review_verdict");
+    }
+}
@@ -1,7 +1,11 @@
 use crate::config::{AgentBackend, AgentConfig, ValidateStep};
-use edda_sandbox::{ExecResult, Sandbox};
+use crate::diff;
+use crate::report::{RunReportCollector, TrajectoryEvent};
+use edda_sandbox::{ExecResult, Sandbox, ShellChunk};
 use eyre::{Result, bail};
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
 fn agent_cmd(agent: &AgentConfig, prompt: &str, trajectory: bool) -> String {
@@ -26,35 +30,61 @@ fn agent_cmd(agent: &AgentConfig, prompt: &str, trajectory: bool) -> String {
     }
 }
 
-fn log_exec(result: &ExecResult, step: &str) {
+/// whether `plan`/`work`/`review`/`run_validate_step` actually exec against the sandbox, or
+/// just render the command they would have run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecMode {
+    #[default]
+    Real,
+    DryRun,
+}
+
+/// one aligned phase/step name + command row of a dry-run command preview
+pub fn simulation_text(name: &str, command: &str) -> String {
+    format!("{name:<10}  {command}")
+}
+
+/// replace every occurrence of a resolved secret value with a fixed placeholder, so captured
+/// command output can be logged without leaking `container.secrets`/`${secret:NAME}` values that
+/// a command dumped into its own stdout/stderr (e.g. a build tool printing its env on failure).
+/// Not a substitute for keeping secrets out of the serialized config in the first place — see
+/// `ContainerConfig::resolve_secrets` — just the last line of defense once a value has already
+/// reached process output.
+fn redact(text: &str, secrets: &HashMap<String, String>) -> String {
+    let mut redacted = text.to_string();
+    for value in secrets.values() {
+        if !value.is_empty() {
+            redacted = redacted.replace(value.as_str(), "[REDACTED]");
+        }
+    }
+    redacted
+}
+
+fn log_exec(result: &ExecResult, step: &str, secrets: &HashMap<String, String>) {
     debug!(
         step,
         exit_code = result.exit_code,
         stdout_len = result.stdout.len(),
         stderr_len = result.stderr.len(),
-        stdout_tail = %truncate_tail(&result.stdout, 500),
-        stderr_tail = %truncate_tail(&result.stderr, 500),
+        stdout_tail = %truncate_tail(&redact(&result.stdout, secrets), 500),
+        stderr_tail = %truncate_tail(&redact(&result.stderr, secrets), 500),
         "exec output"
     );
 }
 
-fn check_exec(result: &ExecResult, step: &str) -> Result<()> {
+fn check_exec(result: &ExecResult, step: &str, secrets: &HashMap<String, String>) -> Result<()> {
     if result.exit_code != 0 {
-        warn!(
-            step,
-            exit_code = result.exit_code,
-            stdout = %result.stdout,
-            stderr = %result.stderr,
-            "step failed"
-        );
+        let stdout = redact(&result.stdout, secrets);
+        let stderr = redact(&result.stderr, secrets);
+        warn!(step, exit_code = result.exit_code, stdout = %stdout, stderr = %stderr, "step failed");
         bail!(
             "{step} failed (exit {}):\nstdout: {}\nstderr: {}",
             result.exit_code,
-            result.stdout,
-            result.stderr
+            stdout,
+            stderr
         );
     }
-    log_exec(result, step);
+    log_exec(result, step, secrets);
     Ok(())
 }
 
@@ -111,8 +141,82 @@ enum ContentBlock {
     Other,
 }
 
-/// log each line of a stream-json trajectory
-fn log_trajectory(stdout: &str, step: &str) {
+/// cumulative cost/turn/error counters for one forge run, aggregated across every agent
+/// invocation's `result`-type trajectory line
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunMetrics {
+    pub cost_usd: f64,
+    pub turns: u32,
+    pub errors: u32,
+}
+
+impl RunMetrics {
+    fn record(&mut self, line: &TrajectoryLine) {
+        self.cost_usd += line.total_cost_usd.unwrap_or(0.0);
+        self.turns += line.num_turns.unwrap_or(0);
+        if line.is_error.unwrap_or(false) {
+            self.errors += 1;
+        }
+    }
+
+    fn accumulate(&mut self, other: RunMetrics) {
+        self.cost_usd += other.cost_usd;
+        self.turns += other.turns;
+        self.errors += other.errors;
+    }
+}
+
+/// hard caps on a run's cumulative cost/turns; `None` means unbounded
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunBudget {
+    pub max_cost_usd: Option<f64>,
+    pub max_turns: Option<u32>,
+}
+
+impl RunBudget {
+    /// bail if `metrics` has already pushed past this budget
+    fn check(&self, metrics: &RunMetrics, step: &str) -> Result<()> {
+        if let Some(max_cost_usd) = self.max_cost_usd {
+            if metrics.cost_usd > max_cost_usd {
+                bail!(
+                    "budget exceeded at {step}: cost ${:.4} > max ${:.4}",
+                    metrics.cost_usd, max_cost_usd
+                );
+            }
+        }
+        if let Some(max_turns) = self.max_turns {
+            if metrics.turns > max_turns {
+                bail!(
+                    "budget exceeded at {step}: {} turns > max {max_turns}",
+                    metrics.turns
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// replace every occurrence of a resolved secret value inside a JSON value's serialized form,
+/// re-parsing it back into structured JSON afterwards (falling back to a plain string if the
+/// substitution broke the JSON syntax, which only happens if a secret value itself contains a
+/// quote or brace)
+fn redact_json(value: &serde_json::Value, secrets: &HashMap<String, String>) -> serde_json::Value {
+    let serialized = serde_json::to_string(value).unwrap_or_default();
+    let redacted = redact(&serialized, secrets);
+    serde_json::from_str(&redacted).unwrap_or(serde_json::Value::String(redacted))
+}
+
+/// log each line of a stream-json trajectory, pushing a [`TrajectoryEvent`] per line into
+/// `report` and returning this invocation's aggregated cost/turns. `secrets` is redacted out of
+/// everything logged or persisted, same as `log_exec`/`check_exec` — an agent shelling out to
+/// something that echoes its own environment can put a resolved secret in its own trajectory.
+fn log_trajectory(
+    stdout: &str,
+    step: &str,
+    report: &RunReportCollector,
+    secrets: &HashMap<String, String>,
+) -> RunMetrics {
+    let mut metrics = RunMetrics::default();
     for line in stdout.lines() {
         let line = line.trim();
         if line.is_empty() {
@@ -131,11 +235,22 @@ fn log_trajectory(stdout: &str, step: &str) {
                     for block in &msg.content {
                         match block {
                             ContentBlock::Text { text } => {
-                                info!(step, text = %truncate_tail(text, 200), "agent text");
+                                let text = redact(text, secrets);
+                                info!(step, text = %truncate_tail(&text, 200), "agent text");
+                                report.push(TrajectoryEvent::AgentText {
+                                    step: step.to_string(),
+                                    text,
+                                });
                             }
                             ContentBlock::ToolUse { name, input } => {
-                                let args = serde_json::to_string(input).unwrap_or_default();
-                                info!(step, tool = %name, args = %truncate_tail(&args, 200), "agent tool_use");
+                                let args = redact_json(input, secrets);
+                                let args_str = serde_json::to_string(&args).unwrap_or_default();
+                                info!(step, tool = %name, args = %truncate_tail(&args_str, 200), "agent tool_use");
+                                report.push(TrajectoryEvent::ToolUse {
+                                    step: step.to_string(),
+                                    name: name.clone(),
+                                    args,
+                                });
                             }
                             ContentBlock::Other => {}
                         }
@@ -148,21 +263,58 @@ fn log_trajectory(stdout: &str, step: &str) {
                         serde_json::Value::String(s) => s.clone(),
                         other => serde_json::to_string(other).unwrap_or_default(),
                     };
+                    let s = redact(&s, secrets);
                     debug!(step, result = %truncate_tail(&s, 300), "tool result");
+                    report.push(TrajectoryEvent::ToolResult {
+                        step: step.to_string(),
+                        result: s,
+                    });
                 }
             }
             "result" => {
-                info!(
-                    step,
-                    turns = parsed.num_turns.unwrap_or(0),
-                    cost_usd = parsed.total_cost_usd.unwrap_or(0.0),
-                    is_error = parsed.is_error.unwrap_or(false),
-                    "agent finished"
-                );
+                let turns = parsed.num_turns.unwrap_or(0);
+                let cost_usd = parsed.total_cost_usd.unwrap_or(0.0);
+                let is_error = parsed.is_error.unwrap_or(false);
+                info!(step, turns, cost_usd, is_error, "agent finished");
+                report.push(TrajectoryEvent::StepFinished {
+                    step: step.to_string(),
+                    turns,
+                    cost_usd,
+                    is_error,
+                });
+                metrics.record(&parsed);
             }
             _ => {}
         }
     }
+    metrics
+}
+
+/// pull the agent's final top-level text reply out of a stream-json trajectory; used by
+/// `review` to recover the APPROVED/REJECTED verdict line once it runs with trajectory
+/// logging (and therefore budget tracking) turned on
+fn last_trajectory_text(stdout: &str) -> String {
+    let mut text = String::new();
+    for line in stdout.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Ok(parsed) = serde_json::from_str::<TrajectoryLine>(line) else {
+            continue;
+        };
+        if parsed.msg_type != "assistant" {
+            continue;
+        }
+        if let Some(msg) = &parsed.message {
+            for block in &msg.content {
+                if let ContentBlock::Text { text: block_text } = block {
+                    text = block_text.clone();
+                }
+            }
+        }
+    }
+    text
 }
 
 /// ask the agent to decompose the prompt into a checkbox task list (tasks.md)
@@ -171,7 +323,13 @@ pub async fn plan(
     agent: &AgentConfig,
     prompt: &str,
     language: &str,
+    budget: &RunBudget,
+    metrics: &mut RunMetrics,
+    report: &RunReportCollector,
+    mode: ExecMode,
+    secrets: &HashMap<String, String>,
 ) -> Result<()> {
+    budget.check(metrics, "Plan")?;
     let instruction = format!(
         "You are working in /app, a {language} project. \
          The user wants: {prompt}\n\n\
@@ -183,11 +341,18 @@ pub async fn plan(
          Focus on the public API, data structures, and key algorithms. \
          Do NOT write any code yet — only the task list."
     );
+    let command = agent_cmd(agent, &instruction, true);
+
+    if mode == ExecMode::DryRun {
+        println!("{}", simulation_text("Plan", &command));
+        return Ok(());
+    }
 
     info!("creating task plan");
-    let result = sandbox.exec(&agent_cmd(agent, &instruction, true)).await?;
-    log_trajectory(&result.stdout, "Plan");
-    check_exec(&result, "Plan")?;
+    let result = sandbox.exec(&command).await?;
+    metrics.accumulate(log_trajectory(&result.stdout, "Plan", report, secrets));
+    check_exec(&result, "Plan", secrets)?;
+    budget.check(metrics, "Plan")?;
 
     let task_list = sandbox.read_file("/app/tasks.md").await?;
     if task_list.trim().is_empty() {
@@ -224,11 +389,8 @@ pub fn parse_task_stats(task_list: &str) -> TaskStats {
     TaskStats { done, pending, done_tasks, pending_tasks }
 }
 
-/// ask the agent to work on unchecked tasks and check them off
-pub async fn work(sandbox: &mut impl Sandbox, agent: &AgentConfig, language: &str) -> Result<()> {
-    let task_list = read_tasks(sandbox).await?;
-
-    let instruction = format!(
+fn work_instruction(language: &str, task_list: &str) -> String {
+    format!(
         "You are working in /app, a {language} project. \
          Here is the current task list from /app/tasks.md:\n\n{task_list}\n\n\
          Work on the unchecked tasks (- [ ]). For each task you complete, \
@@ -238,12 +400,36 @@ pub async fn work(sandbox: &mut impl Sandbox, agent: &AgentConfig, language: &st
          IMPORTANT: Do NOT create summary/report files (SUMMARY.md, REPORT.md, etc.), \
          scratch test scripts at the project root, or virtual environments. \
          Only create files that are part of the project deliverable."
-    );
+    )
+}
+
+/// ask the agent to work on unchecked tasks and check them off
+pub async fn work(
+    sandbox: &mut impl Sandbox,
+    agent: &AgentConfig,
+    language: &str,
+    budget: &RunBudget,
+    metrics: &mut RunMetrics,
+    report: &RunReportCollector,
+    mode: ExecMode,
+    secrets: &HashMap<String, String>,
+) -> Result<()> {
+    budget.check(metrics, "Work")?;
+
+    if mode == ExecMode::DryRun {
+        let instruction = work_instruction(language, "<current tasks.md contents>");
+        println!("{}", simulation_text("Work", &agent_cmd(agent, &instruction, true)));
+        return Ok(());
+    }
+
+    let task_list = read_tasks(sandbox).await?;
+    let instruction = work_instruction(language, &task_list);
 
     info!("working on unchecked tasks");
     let result = sandbox.exec(&agent_cmd(agent, &instruction, true)).await?;
-    log_trajectory(&result.stdout, "Work");
-    check_exec(&result, "Work")?;
+    metrics.accumulate(log_trajectory(&result.stdout, "Work", report, secrets));
+    check_exec(&result, "Work", secrets)?;
+    budget.check(metrics, "Work")?;
     Ok(())
 }
 
@@ -267,14 +453,67 @@ pub async fn read_tasks(sandbox: &mut impl Sandbox) -> Result<String> {
     Ok(content)
 }
 
+/// one reviewer note tied to a specific file and line, parsed from a rejected verdict's
+/// `<path>:<line> — <note>` follow-up lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewFinding {
+    pub path: String,
+    pub line: u32,
+    pub note: String,
+}
+
 pub enum ReviewVerdict {
     Approved,
-    Rejected { feedback: String },
+    Rejected { feedback: String, findings: Vec<ReviewFinding> },
     InvalidFormat,
 }
 
+/// parse a single `<path>:<line> — <note>` finding line; returns `None` for anything else
+/// (blank lines, stray prose) so callers can filter a rejection's follow-up lines freely
+fn parse_finding_line(line: &str) -> Option<ReviewFinding> {
+    let (location, note) = line.split_once('—')?;
+    let (path, line_no) = location.trim().rsplit_once(':')?;
+    let line_no: u32 = line_no.trim().parse().ok()?;
+    Some(ReviewFinding { path: path.trim().to_string(), line: line_no, note: note.trim().to_string() })
+}
+
+fn review_instruction(language: &str, diff_context: &str, task_list: &str) -> String {
+    format!(
+        "You are a {language} code reviewer working in /app. Here are the staged changes, \
+         with line numbers in the new file:\n\n{diff_context}\n\n\
+         Task list:\n{task_list}\n\n\
+         Check for correctness and bugs only. Do NOT write or modify any files.\n\n\
+         Respond ONLY with one of:\n\
+         APPROVED\n\
+         REJECTED: <short reason>\n\
+         <path>:<line> — <note>\n\
+         <path>:<line> — <note>\n\n\
+         The finding lines are optional and only follow a REJECTED verdict, one per issue. \
+         No analysis, no markdown, no other explanation — just the verdict line(s)."
+    )
+}
+
 /// ask the agent to review the diff
-pub async fn review(sandbox: &mut impl Sandbox, agent: &AgentConfig, language: &str, diff_pathspec: &str) -> Result<ReviewVerdict> {
+pub async fn review(
+    sandbox: &mut impl Sandbox,
+    agent: &AgentConfig,
+    language: &str,
+    diff_pathspec: &str,
+    budget: &RunBudget,
+    metrics: &mut RunMetrics,
+    report: &RunReportCollector,
+    mode: ExecMode,
+    secrets: &HashMap<String, String>,
+) -> Result<ReviewVerdict> {
+    budget.check(metrics, "Review")?;
+
+    if mode == ExecMode::DryRun {
+        let instruction =
+            review_instruction(language, "<staged diff>", "<current tasks.md contents>");
+        println!("{}", simulation_text("Review", &agent_cmd(agent, &instruction, true)));
+        return Ok(ReviewVerdict::Approved);
+    }
+
     let task_list = match read_tasks(sandbox).await {
         Ok(tasks) => tasks,
         Err(e) => {
@@ -282,59 +521,258 @@ pub async fn review(sandbox: &mut impl Sandbox, agent: &AgentConfig, language: &
             String::new()
         }
     };
-    // stage all changes so the agent can inspect via `git diff --cached`
+    // stage all changes so the diff (and the agent's own `git diff --cached`) reflects them
     let stage = sandbox.exec("git add -A").await?;
     if stage.exit_code != 0 {
         bail!("git add -A failed: {}", stage.stderr);
     }
 
-    let instruction = format!(
-        "You are a {language} code reviewer working in /app. \
-         Review the staged changes (run `git diff --cached {diff_pathspec}` to see the diff).\n\n\
-         Task list:\n{task_list}\n\n\
-         Check for correctness and bugs only. Do NOT write or modify any files.\n\n\
-         Respond ONLY with one of:\n\
-         APPROVED\n\
-         REJECTED: <short reason>\n\n\
-         No analysis, no markdown, no explanation — just the verdict line."
-    );
+    let diff_result = sandbox.exec(&format!("git diff --cached {diff_pathspec}")).await?;
+    let files = diff::parse_unified_diff(&diff_result.stdout);
+    let diff_context = diff::render_for_review(&files);
+
+    let instruction = review_instruction(language, &diff_context, &task_list);
 
     info!("reviewing code");
-    let result = sandbox.exec(&agent_cmd(agent, &instruction, false)).await?;
-    check_exec(&result, "Review")?;
+    let result = sandbox.exec(&agent_cmd(agent, &instruction, true)).await?;
+    metrics.accumulate(log_trajectory(&result.stdout, "Review", report, secrets));
+    check_exec(&result, "Review", secrets)?;
+    budget.check(metrics, "Review")?;
 
-    let output = result.stdout.trim().to_string();
-    for line in output.lines() {
+    let output = last_trajectory_text(&result.stdout).trim().to_string();
+    let output_lines: Vec<&str> = output.lines().collect();
+    for (i, line) in output_lines.iter().enumerate() {
         let trimmed = line.trim();
         if trimmed.starts_with("APPROVED") {
+            report.push(TrajectoryEvent::ReviewVerdict { approved: true, feedback: None });
             return Ok(ReviewVerdict::Approved);
         }
         if trimmed.starts_with("REJECTED") {
-            let feedback = output
+            let feedback = trimmed
                 .split_once("REJECTED")
                 .map(|x| x.1)
                 .unwrap_or("")
+                .trim_start_matches(':')
                 .trim()
                 .to_string();
-            return Ok(ReviewVerdict::Rejected { feedback });
+            let findings: Vec<ReviewFinding> = output_lines[i + 1..]
+                .iter()
+                .filter_map(|l| parse_finding_line(l.trim()))
+                .collect();
+            report.push(TrajectoryEvent::ReviewVerdict {
+                approved: false,
+                feedback: Some(feedback.clone()),
+            });
+            return Ok(ReviewVerdict::Rejected { feedback, findings });
         }
     }
 
     warn!("review output did not contain APPROVED/REJECTED");
+    report.push(TrajectoryEvent::ReviewVerdict {
+        approved: false,
+        feedback: Some("invalid format".to_string()),
+    });
     Ok(ReviewVerdict::InvalidFormat)
 }
 
-/// run a single validation step
+/// the classified outcome of running (and possibly retrying) a validation step, carrying the
+/// final attempt's [`ExecResult`] so callers can still report its stdout/stderr
+#[derive(Debug)]
+pub enum ValidateStatus {
+    Passed { result: ExecResult },
+    /// failed at least once before eventually passing within `step.retries`
+    Flaky { attempts: u32, result: ExecResult },
+    Failed { result: ExecResult },
+}
+
+/// run a single validation step, streaming its output as it becomes available and cancelling it
+/// if `step.timeout_secs` elapses first. Retries a failing command up to `step.retries` times,
+/// reporting [`ValidateStatus::Flaky`] instead of [`ValidateStatus::Passed`] if a later attempt
+/// succeeded.
 pub async fn run_validate_step(
     sandbox: &mut impl Sandbox,
     step: &ValidateStep,
-) -> Result<ExecResult> {
-    info!(step = %step.name, command = %step.command, "running validation step");
-    let result = sandbox.exec(&step.command).await?;
-    debug!(
-        step = %step.name,
-        exit_code = result.exit_code,
-        "validation step finished"
-    );
-    Ok(result)
+    report: &RunReportCollector,
+    mode: ExecMode,
+    secrets: &HashMap<String, String>,
+) -> Result<ValidateStatus> {
+    if mode == ExecMode::DryRun {
+        println!("{}", simulation_text(&step.name, &step.command));
+        return Ok(ValidateStatus::Passed {
+            result: ExecResult { exit_code: 0, stdout: String::new(), stderr: String::new() },
+        });
+    }
+
+    let max_attempts = step.retries + 1;
+    let mut attempt = 0u32;
+    loop {
+        attempt += 1;
+        info!(step = %step.name, command = %step.command, attempt, "running validation step");
+        let timeout = step.timeout_secs.map(Duration::from_secs);
+        let mut on_chunk = |chunk: ShellChunk| match chunk {
+            ShellChunk::Stdout(bytes) => {
+                let line = redact(&String::from_utf8_lossy(&bytes), secrets);
+                info!(step = %step.name, line, "stdout");
+            }
+            ShellChunk::Stderr(bytes) => {
+                let line = redact(&String::from_utf8_lossy(&bytes), secrets);
+                info!(step = %step.name, line, "stderr");
+            }
+            ShellChunk::Exited(_) => {}
+        };
+        let start = Instant::now();
+        let result = sandbox
+            .exec_streaming(&step.command, &mut on_chunk, timeout)
+            .await?;
+        let duration_ms = start.elapsed().as_millis();
+        debug!(
+            step = %step.name,
+            exit_code = result.exit_code,
+            attempt,
+            "validation step finished"
+        );
+        report.push(TrajectoryEvent::ValidateResult {
+            step: step.name.clone(),
+            exit_code: result.exit_code,
+            duration_ms,
+        });
+
+        if result.exit_code == 0 {
+            return Ok(if attempt > 1 {
+                warn!(step = %step.name, attempts = attempt, "validation step passed after retrying, flagging as flaky");
+                ValidateStatus::Flaky { attempts: attempt, result }
+            } else {
+                ValidateStatus::Passed { result }
+            });
+        }
+        if attempt >= max_attempts {
+            return Ok(ValidateStatus::Failed { result });
+        }
+        warn!(step = %step.name, attempt, max_attempts, "validation step failed, retrying");
+    }
+}
+
+/// a tiny deterministic PRNG (xorshift64*) used only to reproducibly shuffle validation-step
+/// ordering — not suitable for anything security-sensitive
+struct Xorshift64(u64);
+
+impl Xorshift64 {
+    fn new(seed: u64) -> Self {
+        // xorshift64* is undefined for a zero state
+        Self(if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed })
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Fisher-Yates shuffle
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next_u64() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+/// groups `steps` into the units `run_validate_batch` executes one at a time: a run of
+/// consecutive `parallel = true` steps is one batch (run concurrently), while a
+/// `parallel = false` step always forms its own singleton batch, in order. If `shuffle_seed` is
+/// given, each batch of independent (parallel) steps is deterministically reshuffled — the same
+/// seed always produces the same order, so a run that shakes out an ordering bug can be
+/// reproduced exactly.
+pub fn validate_batches(steps: &[ValidateStep], shuffle_seed: Option<u64>) -> Vec<Vec<&ValidateStep>> {
+    let mut batches: Vec<Vec<&ValidateStep>> = Vec::new();
+    for step in steps {
+        if step.parallel {
+            if let Some(last) = batches.last_mut().filter(|b| b.last().is_some_and(|s| s.parallel)) {
+                last.push(step);
+                continue;
+            }
+        }
+        batches.push(vec![step]);
+    }
+
+    if let Some(seed) = shuffle_seed {
+        for (i, batch) in batches.iter_mut().enumerate() {
+            if batch.len() > 1 {
+                Xorshift64::new(seed.wrapping_add(i as u64)).shuffle(batch);
+            }
+        }
+    }
+
+    batches
+}
+
+/// the outcome of one step within a batch, paired with its name for reporting
+pub struct StepOutcome {
+    pub name: String,
+    pub outcome: Result<ValidateStatus>,
+}
+
+/// runs every step in `batch` to completion. A single-step batch just runs on `sandbox`. A
+/// multi-step (parallel) batch first tries to [`Sandbox::fork`] an independent sandbox per step
+/// so they can run concurrently via `futures_util::future::join_all`; if this runtime doesn't
+/// support forking, the batch falls back to running sequentially on `sandbox` instead. Likewise,
+/// if forking works but the forks don't share a filesystem with `sandbox` (Dagger: forks are
+/// independent container branches), every step in the batch fails outright rather than silently
+/// losing whatever files the parallel steps wrote — see [`Sandbox::fork_shares_filesystem`].
+pub async fn run_validate_batch(
+    sandbox: &mut impl Sandbox,
+    batch: &[&ValidateStep],
+    report: &RunReportCollector,
+    mode: ExecMode,
+    secrets: &HashMap<String, String>,
+) -> Vec<StepOutcome> {
+    if batch.len() == 1 || mode == ExecMode::DryRun {
+        let mut results = Vec::with_capacity(batch.len());
+        for step in batch {
+            let outcome = run_validate_step(sandbox, step, report, mode, secrets).await;
+            results.push(StepOutcome { name: step.name.clone(), outcome });
+        }
+        return results;
+    }
+
+    if !sandbox.fork_shares_filesystem() {
+        return batch
+            .iter()
+            .map(|step| StepOutcome {
+                name: step.name.clone(),
+                outcome: Err(eyre::eyre!(
+                    "'{}' is one of {} parallel steps, but this sandbox backend's fork() \
+                     branches into an independent filesystem — running them concurrently would \
+                     silently drop any files they write. Mark these steps `parallel = false`.",
+                    step.name,
+                    batch.len()
+                )),
+            })
+            .collect();
+    }
+
+    let mut forks = Vec::with_capacity(batch.len());
+    for _ in batch {
+        match sandbox.fork().await {
+            Ok(f) => forks.push(f),
+            Err(e) => {
+                debug!(error = %e, "sandbox does not support fork, running parallel batch sequentially");
+                let mut results = Vec::with_capacity(batch.len());
+                for step in batch {
+                    let outcome = run_validate_step(sandbox, step, report, mode, secrets).await;
+                    results.push(StepOutcome { name: step.name.clone(), outcome });
+                }
+                return results;
+            }
+        }
+    }
+
+    let futures = batch.iter().zip(forks.into_iter()).map(|(step, mut fork)| async move {
+        let outcome = run_validate_step(&mut fork, step, report, mode, secrets).await;
+        StepOutcome { name: step.name.clone(), outcome }
+    });
+    futures_util::future::join_all(futures).await
 }
@@ -0,0 +1,152 @@
+use eyre::{Result, bail};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tracing::info;
+
+/// materializes a project source onto the host filesystem before it's copied/mounted into the
+/// sandbox. `LocalSource` just validates an existing directory; `GitSource` clones a remote and
+/// resolves submodules. A plain enum (rather than `dyn SourceBackend`) for the same reason as
+/// [`crate::publish::Publisher`]: the trait's only async method can't be boxed on stable Rust,
+/// and there are only ever two kinds to choose between.
+pub trait SourceBackend {
+    async fn resolve(&self) -> Result<PathBuf>;
+}
+
+pub struct LocalSource {
+    path: PathBuf,
+}
+
+impl LocalSource {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl SourceBackend for LocalSource {
+    async fn resolve(&self) -> Result<PathBuf> {
+        if !self.path.exists() {
+            bail!("source path does not exist: {}", self.path.display());
+        }
+        Ok(self.path.clone())
+    }
+}
+
+pub struct GitSource {
+    url: String,
+    git_ref: Option<String>,
+    /// scratch directory the clone lands in; wiped and recreated on each run
+    dest: PathBuf,
+}
+
+impl GitSource {
+    pub fn new(url: String, git_ref: Option<String>, dest: PathBuf) -> Self {
+        Self { url, git_ref, dest }
+    }
+}
+
+impl SourceBackend for GitSource {
+    async fn resolve(&self) -> Result<PathBuf> {
+        if self.dest.exists() {
+            std::fs::remove_dir_all(&self.dest)?;
+        }
+        if let Some(parent) = self.dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        info!(url = %self.url, dest = %self.dest.display(), "cloning remote source");
+        let status = Command::new("git")
+            .args(["clone", "--quiet", &self.url])
+            .arg(&self.dest)
+            .status()
+            .await
+            .map_err(|e| eyre::eyre!("failed to spawn git clone: {e}"))?;
+        if !status.success() {
+            bail!("git clone of '{}' failed", self.url);
+        }
+        update_submodules(&self.dest).await?;
+
+        if let Some(git_ref) = &self.git_ref {
+            info!(git_ref = %git_ref, "checking out source ref");
+            let status = Command::new("git")
+                .args(["checkout", "--quiet", git_ref])
+                .current_dir(&self.dest)
+                .status()
+                .await
+                .map_err(|e| eyre::eyre!("failed to spawn git checkout: {e}"))?;
+            if !status.success() {
+                bail!("git checkout of '{git_ref}' failed");
+            }
+            update_submodules(&self.dest).await?;
+        }
+
+        Ok(self.dest.clone())
+    }
+}
+
+/// `git submodule update --init --recursive`; a no-op on a repo with no `.gitmodules`. Run once
+/// right after the clone and again after any ref checkout, since checking out a different
+/// commit can introduce submodules that weren't present at clone time.
+async fn update_submodules(repo_dir: &Path) -> Result<()> {
+    let status = Command::new("git")
+        .args(["submodule", "update", "--init", "--recursive"])
+        .current_dir(repo_dir)
+        .status()
+        .await
+        .map_err(|e| eyre::eyre!("failed to spawn git submodule update: {e}"))?;
+    if !status.success() {
+        bail!("git submodule update --init --recursive failed");
+    }
+    Ok(())
+}
+
+/// distinguishes a `--source` given as a clone-able URL from one given as a local path: explicit
+/// schemes, the conventional `.git` suffix, or scp-like `user@host:path` shorthand.
+pub fn is_git_url(spec: &str) -> bool {
+    spec.starts_with("git://")
+        || spec.starts_with("https://")
+        || spec.starts_with("http://")
+        || spec.starts_with("ssh://")
+        || spec.ends_with(".git")
+        || (spec.contains('@') && spec.contains(':') && !Path::new(spec).exists())
+}
+
+/// a short, filesystem-safe slug from the last path segment of a clone URL, used to name the
+/// scratch clone directory
+fn slugify(url: &str) -> String {
+    let name = url
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(url)
+        .trim_end_matches(".git");
+    let slug: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    if slug.is_empty() { "source".to_string() } else { slug }
+}
+
+/// builds the `--source` backend: a `LocalSource` for a plain path, a `GitSource` (cloning into
+/// a scratch directory under the system temp dir) for anything [`is_git_url`] recognizes
+pub fn resolve_backend(spec: &str, git_ref: Option<String>) -> Source {
+    if is_git_url(spec) {
+        let dest = std::env::temp_dir().join(format!("edda-forge-source-{}", slugify(spec)));
+        Source::Git(GitSource::new(spec.to_string(), git_ref, dest))
+    } else {
+        Source::Local(LocalSource::new(PathBuf::from(spec)))
+    }
+}
+
+pub enum Source {
+    Local(LocalSource),
+    Git(GitSource),
+}
+
+impl Source {
+    pub async fn resolve(&self) -> Result<PathBuf> {
+        match self {
+            Source::Local(s) => s.resolve().await,
+            Source::Git(s) => s.resolve().await,
+        }
+    }
+}
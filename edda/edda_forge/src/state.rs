@@ -1,41 +1,16 @@
 use std::collections::HashMap;
 use std::fmt;
 
-#[derive(Debug, Clone)]
-pub enum Phase {
-    Tests,
-    Code,
-}
-
-impl fmt::Display for Phase {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Phase::Tests => write!(f, "Tests"),
-            Phase::Code => write!(f, "Code"),
-        }
-    }
-}
-
 #[derive(Debug, Clone)]
 pub enum State {
     Init {
         prompt: String,
     },
-    RewriteTask {
+    Plan {
         prompt: String,
     },
-    LoadTaskList {
-        task_list: String,
-    },
-    WriteTests {
-        task_list: String,
-    },
-    WriteCode {
-        task_list: String,
-        context: Option<String>,
-    },
+    Work,
     Validate {
-        phase: Phase,
         step_idx: usize,
     },
     Review,
@@ -50,29 +25,29 @@ impl State {
     pub fn is_terminal(&self) -> bool {
         matches!(self, State::Done | State::Failed { .. })
     }
+
+    /// the `RetryTracker` edge key this `old -> new` transition represents, if it's a
+    /// backtrack rather than forward progress
+    pub fn backtrack_edge(old: &State, new: &State) -> Option<&'static str> {
+        match (old, new) {
+            (State::Validate { .. }, State::Work) => Some("Validate->Work"),
+            (State::Review, State::Work) => Some("Review->Work"),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for State {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             State::Init { .. } => write!(f, "Init"),
-            State::RewriteTask { .. } => write!(f, "RewriteTask"),
-            State::LoadTaskList { .. } => write!(f, "LoadTaskList"),
-            State::WriteTests { .. } => write!(f, "WriteTests"),
-            State::WriteCode { context, .. } => {
-                if context.is_some() {
-                    write!(f, "WriteCode(retry)")
-                } else {
-                    write!(f, "WriteCode")
-                }
-            }
-            State::Validate { phase, step_idx } => {
-                write!(f, "Validate({}, step={})", phase, step_idx)
-            }
+            State::Plan { .. } => write!(f, "Plan"),
+            State::Work => write!(f, "Work"),
+            State::Validate { step_idx } => write!(f, "Validate(step={step_idx})"),
             State::Review => write!(f, "Review"),
             State::Export => write!(f, "Export"),
             State::Done => write!(f, "Done"),
-            State::Failed { reason } => write!(f, "Failed({})", reason),
+            State::Failed { reason } => write!(f, "Failed({reason})"),
         }
     }
 }
@@ -103,3 +78,40 @@ impl RetryTracker {
         self.counts.get(edge).copied().unwrap_or(0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_retry_allows_up_to_max_then_denies() {
+        let mut tracker = RetryTracker::new(2);
+        assert!(tracker.try_retry("Validate->Work"));
+        assert!(tracker.try_retry("Validate->Work"));
+        assert!(!tracker.try_retry("Validate->Work"));
+        assert_eq!(tracker.count("Validate->Work"), 3);
+    }
+
+    #[test]
+    fn test_backtrack_edge_identifies_retries() {
+        let validate = State::Validate { step_idx: 1 };
+        assert_eq!(
+            State::backtrack_edge(&validate, &State::Work),
+            Some("Validate->Work")
+        );
+        assert_eq!(
+            State::backtrack_edge(&State::Review, &State::Work),
+            Some("Review->Work")
+        );
+        assert_eq!(State::backtrack_edge(&State::Work, &validate), None);
+    }
+
+    #[test]
+    fn test_try_retry_tracks_edges_independently() {
+        let mut tracker = RetryTracker::new(1);
+        assert!(tracker.try_retry("Validate->Work"));
+        assert!(tracker.try_retry("Review->Work"));
+        assert_eq!(tracker.count("Validate->Work"), 1);
+        assert_eq!(tracker.count("Review->Work"), 1);
+    }
+}
@@ -0,0 +1,299 @@
+use eyre::Result;
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Notify, mpsc};
+
+const LOG_LINES_KEPT: usize = 500;
+const TICK: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepStatus {
+    Pending,
+    Running,
+    Passed,
+    Failed,
+}
+
+/// one push from `run_pipeline`/`step` to the dashboard; kept cheap to build since every variant
+/// is sent unconditionally once `--tui` is on
+pub enum TuiUpdate {
+    State(String),
+    Tasks {
+        done: usize,
+        pending: usize,
+        lines: Vec<(bool, String)>,
+    },
+    Retries {
+        validate_retries: usize,
+        review_retries: usize,
+        max_retries: usize,
+    },
+    Step {
+        name: String,
+        status: StepStatus,
+    },
+    Log(String),
+    Shutdown,
+}
+
+/// cheap, cloneable handle to the running dashboard; `emit` never blocks the pipeline since the
+/// channel is unbounded and the dashboard is a passive renderer
+#[derive(Clone)]
+pub struct TuiHandle {
+    tx: mpsc::UnboundedSender<TuiUpdate>,
+    abort: Arc<Notify>,
+}
+
+impl TuiHandle {
+    pub fn emit(&self, update: TuiUpdate) {
+        let _ = self.tx.send(update);
+    }
+
+    pub fn shutdown(&self) {
+        let _ = self.tx.send(TuiUpdate::Shutdown);
+    }
+
+    /// races alongside `tokio::signal::ctrl_c()` in the pipeline loop: raw mode suppresses SIGINT
+    /// generation, so the dashboard's own key-read loop has to notice Ctrl+C/`q` and forward it
+    pub async fn wait_for_abort(this: Option<&TuiHandle>) {
+        match this {
+            Some(handle) => handle.abort.notified().await,
+            None => std::future::pending().await,
+        }
+    }
+}
+
+/// a [`tracing_subscriber::fmt::MakeWriter`] that forwards formatted log lines into the dashboard
+/// instead of stdout, so they render in the scrolling log pane rather than corrupting the
+/// alternate screen
+#[derive(Clone)]
+pub struct TuiLogSink {
+    tx: mpsc::UnboundedSender<TuiUpdate>,
+}
+
+impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for TuiLogSink {
+    type Writer = TuiLogWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        TuiLogWriter { tx: self.tx.clone() }
+    }
+}
+
+pub struct TuiLogWriter {
+    tx: mpsc::UnboundedSender<TuiUpdate>,
+}
+
+impl Write for TuiLogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let line = String::from_utf8_lossy(buf);
+        let line = line.trim_end_matches('\n');
+        if !line.is_empty() {
+            let _ = self.tx.send(TuiUpdate::Log(line.to_string()));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// starts the dashboard render task and returns the handles the rest of `main` needs: something
+/// to push pipeline updates through, something to hand `tracing_subscriber` as its writer, and
+/// the task's join handle so the caller can wait for the terminal to be restored before exiting
+pub fn start() -> (TuiHandle, TuiLogSink, tokio::task::JoinHandle<Result<()>>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let abort = Arc::new(Notify::new());
+    let handle = TuiHandle {
+        tx: tx.clone(),
+        abort: abort.clone(),
+    };
+    let log_sink = TuiLogSink { tx };
+    let join = tokio::spawn(run(rx, abort));
+    (handle, log_sink, join)
+}
+
+#[derive(Default)]
+struct Dashboard {
+    state_name: String,
+    since: Option<Instant>,
+    tasks_done: usize,
+    tasks_pending: usize,
+    task_lines: Vec<(bool, String)>,
+    validate_retries: usize,
+    review_retries: usize,
+    max_retries: usize,
+    steps: Vec<(String, StepStatus)>,
+    log: VecDeque<String>,
+}
+
+impl Dashboard {
+    fn apply(&mut self, update: TuiUpdate) {
+        match update {
+            TuiUpdate::State(name) => {
+                self.state_name = name;
+                self.since = Some(Instant::now());
+            }
+            TuiUpdate::Tasks { done, pending, lines } => {
+                self.tasks_done = done;
+                self.tasks_pending = pending;
+                self.task_lines = lines;
+            }
+            TuiUpdate::Retries { validate_retries, review_retries, max_retries } => {
+                self.validate_retries = validate_retries;
+                self.review_retries = review_retries;
+                self.max_retries = max_retries;
+            }
+            TuiUpdate::Step { name, status } => {
+                if let Some(existing) = self.steps.iter_mut().find(|(n, _)| *n == name) {
+                    existing.1 = status;
+                } else {
+                    self.steps.push((name, status));
+                }
+            }
+            TuiUpdate::Log(line) => {
+                self.log.push_back(line);
+                while self.log.len() > LOG_LINES_KEPT {
+                    self.log.pop_front();
+                }
+            }
+            TuiUpdate::Shutdown => {}
+        }
+    }
+}
+
+/// restores the terminal on drop so an early return (or panic) never leaves the user's shell in
+/// raw/alternate-screen mode
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        crossterm::terminal::enable_raw_mode()?;
+        crossterm::execute!(std::io::stdout(), crossterm::terminal::EnterAlternateScreen)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = crossterm::execute!(std::io::stdout(), crossterm::terminal::LeaveAlternateScreen);
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+}
+
+async fn run(mut rx: mpsc::UnboundedReceiver<TuiUpdate>, abort: Arc<Notify>) -> Result<()> {
+    let _guard = TerminalGuard::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+    let mut dashboard = Dashboard::default();
+    let mut events = crossterm::event::EventStream::new();
+    let mut tick = tokio::time::interval(TICK);
+
+    'outer: loop {
+        tokio::select! {
+            update = rx.recv() => {
+                match update {
+                    Some(TuiUpdate::Shutdown) | None => break 'outer,
+                    Some(update) => dashboard.apply(update),
+                }
+            }
+            Some(Ok(event)) = futures_util::StreamExt::next(&mut events) => {
+                if is_abort_key(&event) {
+                    abort.notify_waiters();
+                    break 'outer;
+                }
+            }
+            _ = tick.tick() => {}
+        }
+        terminal.draw(|frame| render(frame, &dashboard))?;
+    }
+
+    Ok(())
+}
+
+fn is_abort_key(event: &crossterm::event::Event) -> bool {
+    use crossterm::event::{Event, KeyCode, KeyModifiers};
+    matches!(
+        event,
+        Event::Key(key)
+            if key.code == KeyCode::Char('q')
+                || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+    )
+}
+
+fn render(frame: &mut ratatui::Frame, dashboard: &Dashboard) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Percentage(30),
+            Constraint::Percentage(25),
+            Constraint::Min(5),
+        ])
+        .split(frame.area());
+
+    let elapsed = dashboard
+        .since
+        .map(|s| s.elapsed().as_secs())
+        .unwrap_or(0);
+    let header = Paragraph::new(format!("state: {}   elapsed: {elapsed}s", dashboard.state_name))
+        .block(Block::default().borders(Borders::ALL).title("edda-forge"));
+    frame.render_widget(header, rows[0]);
+
+    let task_items: Vec<ListItem> = dashboard
+        .task_lines
+        .iter()
+        .map(|(done, text)| {
+            let marker = if *done { "[x]" } else { "[ ]" };
+            let style = if *done {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(Span::styled(format!("{marker} {text}"), style)))
+        })
+        .collect();
+    let tasks = List::new(task_items).block(Block::default().borders(Borders::ALL).title(format!(
+        "tasks.md ({} done / {} pending)",
+        dashboard.tasks_done, dashboard.tasks_pending
+    )));
+    frame.render_widget(tasks, rows[1]);
+
+    let mut step_items: Vec<ListItem> = dashboard
+        .steps
+        .iter()
+        .map(|(name, status)| {
+            let (label, style) = match status {
+                StepStatus::Pending => ("pending", Style::default().fg(Color::DarkGray)),
+                StepStatus::Running => ("running", Style::default().fg(Color::Yellow)),
+                StepStatus::Passed => ("passed", Style::default().fg(Color::Green)),
+                StepStatus::Failed => ("failed", Style::default().fg(Color::Red)),
+            };
+            ListItem::new(Line::from(Span::styled(format!("{name}: {label}"), style)))
+        })
+        .collect();
+    step_items.push(ListItem::new(format!(
+        "retries — validate: {}/{}  review: {}/{}",
+        dashboard.validate_retries, dashboard.max_retries, dashboard.review_retries, dashboard.max_retries
+    )));
+    let validation = List::new(step_items).block(Block::default().borders(Borders::ALL).title("validation"));
+    frame.render_widget(validation, rows[2]);
+
+    let log_items: Vec<ListItem> = dashboard
+        .log
+        .iter()
+        .rev()
+        .take(rows[3].height.saturating_sub(2) as usize)
+        .rev()
+        .map(|line| ListItem::new(line.as_str()))
+        .collect();
+    let log = List::new(log_items).block(Block::default().borders(Borders::ALL).title("log"));
+    frame.render_widget(log, rows[3]);
+}
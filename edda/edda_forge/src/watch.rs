@@ -0,0 +1,179 @@
+use crate::config::{ForgeConfig, ForgeOverride, MountConfig, is_secret_marker};
+use edda_sandbox::Sandbox;
+use eyre::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+use tracing::{debug, info};
+
+/// how often to re-stat the config file and mount sources for changes
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// mounts to (re-)apply and env vars to (re-)apply, computed by diffing two loaded
+/// `ForgeConfig`s against each other
+#[derive(Debug, Default)]
+pub struct ConfigDelta {
+    pub changed_mounts: Vec<MountConfig>,
+    pub changed_env: HashMap<String, String>,
+}
+
+impl ConfigDelta {
+    pub fn is_empty(&self) -> bool {
+        self.changed_mounts.is_empty() && self.changed_env.is_empty()
+    }
+}
+
+fn diff(old: &ForgeConfig, new: &ForgeConfig) -> ConfigDelta {
+    let changed_mounts = new
+        .mounts
+        .iter()
+        .filter(|m| {
+            !old.mounts.iter().any(|o| {
+                o.host == m.host && o.container == m.container && o.local_target == m.local_target
+            })
+        })
+        .cloned()
+        .collect();
+
+    let changed_env = new
+        .container
+        .env
+        .iter()
+        .filter(|(k, v)| !is_secret_marker(v) && old.container.env.get(*k) != Some(*v))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
+    ConfigDelta {
+        changed_mounts,
+        changed_env,
+    }
+}
+
+fn file_mtime(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn mount_mtimes(config: &ForgeConfig, config_dir: &Path) -> HashMap<PathBuf, Option<SystemTime>> {
+    config
+        .mounts
+        .iter()
+        .filter_map(|m| m.resolve_host_path(config_dir).ok())
+        .map(|p| {
+            let mtime = file_mtime(&p);
+            (p, mtime)
+        })
+        .collect()
+}
+
+/// watches an on-disk `ForgeConfig` (and its mounted source paths) for changes, so a long-running
+/// forge session can pick up edits without restarting the agent or losing checkpoint history.
+/// Applying a detected change is a separate step — see [`apply_delta`] — so the caller stays in
+/// full control of when a live `Sandbox` is mutated.
+pub struct ConfigWatcher {
+    config_path: PathBuf,
+    config_dir: PathBuf,
+    overrides: ForgeOverride,
+    last_config: ForgeConfig,
+    config_mtime: Option<SystemTime>,
+    mount_mtimes: HashMap<PathBuf, Option<SystemTime>>,
+    last_poll: Instant,
+}
+
+impl ConfigWatcher {
+    pub fn new(
+        config_path: &Path,
+        config_dir: &Path,
+        overrides: ForgeOverride,
+        initial: ForgeConfig,
+    ) -> Self {
+        let config_mtime = file_mtime(config_path);
+        let mount_mtimes = mount_mtimes(&initial, config_dir);
+        Self {
+            config_path: config_path.to_path_buf(),
+            config_dir: config_dir.to_path_buf(),
+            overrides,
+            last_config: initial,
+            config_mtime,
+            mount_mtimes,
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// cheaply check whether anything watched has changed since the last call and, if so,
+    /// reload the config and return the delta to apply. Returns `Ok(None)` when it's not yet
+    /// time to poll again, or when a poll found no changes worth applying.
+    pub fn poll(&mut self) -> Result<Option<ConfigDelta>> {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return Ok(None);
+        }
+        self.last_poll = Instant::now();
+
+        let config_changed = file_mtime(&self.config_path) != self.config_mtime;
+        let mounts_changed = mount_mtimes(&self.last_config, &self.config_dir) != self.mount_mtimes;
+        if !config_changed && !mounts_changed {
+            return Ok(None);
+        }
+
+        let new_config = ForgeConfig::load_with_override(&self.config_path, self.overrides.clone())?;
+        let delta = diff(&self.last_config, &new_config);
+
+        self.config_mtime = file_mtime(&self.config_path);
+        self.mount_mtimes = mount_mtimes(&new_config, &self.config_dir);
+        self.last_config = new_config;
+
+        if delta.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(delta))
+        }
+    }
+
+    /// poll for a change and, if one is found, apply it to `sandbox` immediately. Returns
+    /// whether a (possibly partially-applied, see [`apply_delta`]) reload happened.
+    pub async fn poll_and_apply(&mut self, sandbox: &mut impl Sandbox) -> Result<bool> {
+        let Some(delta) = self.poll()? else {
+            return Ok(false);
+        };
+        info!(
+            mounts = delta.changed_mounts.len(),
+            env_vars = delta.changed_env.len(),
+            "config changed on disk, hot-reloading"
+        );
+        apply_delta(&delta, sandbox, &self.config_dir).await?;
+        Ok(true)
+    }
+}
+
+/// apply a `ConfigDelta` to a live sandbox. Mounts are re-synced via
+/// [`edda_sandbox::Sandbox::refresh_from_host`] and env vars via
+/// [`edda_sandbox::Sandbox::set_env`]; a runtime that doesn't support one of those is logged and
+/// skipped rather than failing the whole run, matching how checkpoint/restore degrade elsewhere.
+pub async fn apply_delta(
+    delta: &ConfigDelta,
+    sandbox: &mut impl Sandbox,
+    config_dir: &Path,
+) -> Result<()> {
+    for mount in &delta.changed_mounts {
+        let host_path = mount.resolve_host_path(config_dir)?;
+        match sandbox
+            .refresh_from_host(&host_path.to_string_lossy(), &mount.container)
+            .await
+        {
+            Ok(()) => info!(
+                host = %host_path.display(),
+                container = %mount.container,
+                "hot-reloaded mount"
+            ),
+            Err(e) => debug!(error = %e, "sandbox does not support refresh_from_host, skipping mount reload"),
+        }
+    }
+
+    for (key, value) in &delta.changed_env {
+        match sandbox.set_env(key, value).await {
+            Ok(()) => info!(key, "hot-reloaded env var"),
+            Err(e) => debug!(error = %e, "sandbox does not support set_env, skipping env reload"),
+        }
+    }
+
+    Ok(())
+}
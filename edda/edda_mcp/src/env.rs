@@ -150,6 +150,23 @@ pub fn create_env_example() -> eyre::Result<()> {
 # DATABRICKS_TOKEN=dapi...
 # DATABRICKS_WAREHOUSE_ID=your-warehouse-id
 
+# ============================================
+# Agent Credentials (edda_forge)
+# ============================================
+# Required for the `claude` agent backend (or use Bedrock/Vertex instead)
+# ANTHROPIC_API_KEY=sk-ant-...
+# CLAUDE_CODE_USE_BEDROCK=1
+# AWS_ACCESS_KEY_ID=...
+# CLAUDE_CODE_USE_VERTEX=1
+# GOOGLE_APPLICATION_CREDENTIALS=/path/to/service-account.json
+
+# Required for the `opencode` agent backend, keyed by the model's provider prefix
+# (e.g. `opencode/kimi-k2.5-free` -> no key needed beyond this list; `openai/gpt-...` -> OPENAI_API_KEY)
+# OPENAI_API_KEY=sk-...
+# GOOGLE_API_KEY=...
+# MOONSHOT_API_KEY=...
+# OPENROUTER_API_KEY=...
+
 "#;
 
     std::fs::write(&example_path, example_content)?;
@@ -0,0 +1,81 @@
+use std::collections::{HashMap, VecDeque};
+
+/// bounded ring of checkpoints kept per label, so a long, retry-heavy run doesn't accumulate
+/// an unbounded number of snapshots in memory
+const MAX_CHECKPOINTS_PER_LABEL: usize = 8;
+
+/// opaque handle to a previously-checkpointed sandbox state, returned by
+/// [`crate::Sandbox::checkpoint`] and passed back to [`crate::Sandbox::restore`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointId(pub(crate) String);
+
+impl CheckpointId {
+    pub(crate) fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// maps a label (typically a `State`'s `Display` string, optionally with a step index) to the
+/// checkpoints recorded under it, most recent last. Labels are independent rings, so retrying
+/// one backtrack edge never evicts checkpoints from another.
+#[derive(Debug, Clone, Default)]
+pub struct CheckpointStore {
+    by_label: HashMap<String, VecDeque<CheckpointId>>,
+}
+
+impl CheckpointStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// record a checkpoint under `label`, evicting the oldest entry for that label once the
+    /// ring exceeds [`MAX_CHECKPOINTS_PER_LABEL`]
+    pub fn record(&mut self, label: impl Into<String>, id: CheckpointId) {
+        let ring = self.by_label.entry(label.into()).or_default();
+        ring.push_back(id);
+        if ring.len() > MAX_CHECKPOINTS_PER_LABEL {
+            ring.pop_front();
+        }
+    }
+
+    /// the most recently recorded checkpoint under `label`, if any
+    pub fn latest(&self, label: &str) -> Option<&CheckpointId> {
+        self.by_label.get(label).and_then(|ring| ring.back())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_returns_most_recent_per_label() {
+        let mut store = CheckpointStore::new();
+        store.record("Work", CheckpointId::new("a"));
+        store.record("Work", CheckpointId::new("b"));
+        store.record("Validate(step=0)", CheckpointId::new("c"));
+
+        assert_eq!(store.latest("Work").map(CheckpointId::as_str), Some("b"));
+        assert_eq!(
+            store.latest("Validate(step=0)").map(CheckpointId::as_str),
+            Some("c")
+        );
+        assert_eq!(store.latest("missing"), None);
+    }
+
+    #[test]
+    fn test_ring_is_bounded_per_label() {
+        let mut store = CheckpointStore::new();
+        for i in 0..(MAX_CHECKPOINTS_PER_LABEL + 3) {
+            store.record("Work", CheckpointId::new(i.to_string()));
+        }
+        assert_eq!(
+            store.latest("Work").map(CheckpointId::as_str),
+            Some((MAX_CHECKPOINTS_PER_LABEL + 2).to_string()).as_deref()
+        );
+    }
+}
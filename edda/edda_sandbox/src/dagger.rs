@@ -1,8 +1,11 @@
+use crate::checkpoint::{CheckpointId, CheckpointStore};
+use crate::shell::{ShellChunk, ShellSession};
 use crate::ExecResult;
 use dagger_sdk::core::logger::DynLogger;
 use dagger_sdk::logging::{StdLogger, TracingLogger};
 use eyre::Result;
 use globset::{GlobSet, GlobSetBuilder};
+use std::time::Duration;
 use std::{future::Future, io::Write, sync::Arc};
 
 /// Max number of chained Dagger operations before auto-syncing.
@@ -16,6 +19,8 @@ pub struct Sandbox {
     restricted_files: GlobSet,
     /// Tracks chained operations since last sync to avoid hitting Dagger query depth limits.
     ops_since_sync: usize,
+    /// snapshots recorded at successful state-machine transitions, for `checkpoint`/`restore`
+    checkpoints: CheckpointStore,
 }
 
 impl Sandbox {
@@ -26,6 +31,7 @@ impl Sandbox {
             client,
             restricted_files: GlobSet::empty(),
             ops_since_sync: 0,
+            checkpoints: CheckpointStore::new(),
         }
     }
 
@@ -188,6 +194,12 @@ impl crate::Sandbox for Sandbox {
         Ok(())
     }
 
+    async fn set_env(&mut self, key: &str, value: &str) -> Result<()> {
+        self.ctr = self.ctr.with_env_variable(key, value);
+        self.ops_since_sync += 1;
+        Ok(())
+    }
+
     async fn fork(&self) -> Result<Self>
     where
         Self: Sized,
@@ -200,6 +212,140 @@ impl crate::Sandbox for Sandbox {
             client,
             restricted_files,
             ops_since_sync: self.ops_since_sync,
+            // branches share ancestry: a restore on the fork can still reach checkpoints
+            // recorded before the fork happened
+            checkpoints: self.checkpoints.clone(),
+        })
+    }
+
+    /// Dagger containers are immutable and content-addressed: a fork starts from the same
+    /// `ctr` id but `exec`/`write_file`/etc. on one branch replace only that branch's `self.ctr`,
+    /// never the other's. Anything a forked branch writes is gone once it's dropped — callers
+    /// running steps in parallel on forks must not expect those writes to land back on `self`.
+    fn fork_shares_filesystem(&self) -> bool {
+        false
+    }
+
+    /// sync the container and record its content-addressed id under `label`. Identical
+    /// intermediate states dedupe for free since Dagger container ids are content-addressed.
+    async fn checkpoint(&mut self, label: &str) -> Result<CheckpointId> {
+        self.sync().await?;
+        let id = self
+            .ctr
+            .id()
+            .await
+            .map_err(|e| eyre::eyre!("checkpoint: {e}"))?;
+        let checkpoint_id = CheckpointId::new(id.to_string());
+        self.checkpoints.record(label, checkpoint_id.clone());
+        Ok(checkpoint_id)
+    }
+
+    /// load the container straight from a previously recorded id, discarding any work done
+    /// since. O(1): Dagger resolves content-addressed ids without replaying the query chain.
+    async fn restore(&mut self, id: &CheckpointId) -> Result<()> {
+        self.ctr = self
+            .client
+            .load_container_from_id(id.as_str().to_string().into());
+        self.ops_since_sync = 0;
+        Ok(())
+    }
+
+    /// open an attached session running `program`.
+    ///
+    /// The underlying container exec is request/response, not a live pty, so there's no way to
+    /// stream stdin into an already-running command: the exec only starts once the caller calls
+    /// [`ShellSession::close_stdin`] (or drops the session), at which point everything written
+    /// up to then is batched into a single invocation and its output is delivered as it becomes
+    /// available rather than streamed byte-by-byte. [`crate::RemoteSandbox`] opens its shell
+    /// under a real pty, but it too delivers output only once the program has exited.
+    async fn shell(&mut self, program: &str) -> Result<ShellSession> {
+        self.auto_sync_if_needed().await?;
+
+        let (stdin_tx, mut stdin_rx) = tokio::sync::mpsc::unbounded_channel::<Vec<u8>>();
+        let (chunks_tx, chunks_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let ctr = self.ctr.clone();
+        let program = program.to_string();
+        tokio::spawn(async move {
+            let mut stdin = Vec::new();
+            // blocks until the caller closes stdin (explicitly, or by dropping the session) —
+            // that's the signal this request/response backend uses to mean "run it now"
+            while let Some(chunk) = stdin_rx.recv().await {
+                stdin.extend(chunk);
+            }
+
+            let opts = dagger_sdk::ContainerWithExecOptsBuilder::default()
+                .expect(dagger_sdk::ReturnType::Any)
+                .stdin(String::from_utf8_lossy(&stdin).to_string())
+                .build()
+                .unwrap();
+            let command = vec!["sh".to_string(), "-c".to_string(), program];
+            let ctr = ctr.with_exec_opts(command, opts);
+
+            let exit_code = ctr.exit_code().await.unwrap_or(-1);
+            if let Ok(stdout) = ctr.stdout().await {
+                if !stdout.is_empty() {
+                    let _ = chunks_tx.send(ShellChunk::Stdout(stdout.into_bytes()));
+                }
+            }
+            if let Ok(stderr) = ctr.stderr().await {
+                if !stderr.is_empty() {
+                    let _ = chunks_tx.send(ShellChunk::Stderr(stderr.into_bytes()));
+                }
+            }
+            let _ = chunks_tx.send(ShellChunk::Exited(exit_code as isize));
+        });
+
+        Ok(ShellSession::new(stdin_tx, chunks_rx))
+    }
+
+    /// fetches `exit_code`/`stdout`/`stderr` under an optional deadline, then replays them
+    /// to `on_chunk` line by line. The underlying exec is still request/response rather than
+    /// a true live pipe, but the timeout genuinely cancels a hung command instead of
+    /// blocking forever, and the Validate phase gets line-by-line progress once it lands.
+    async fn exec_streaming(
+        &mut self,
+        command: &str,
+        on_chunk: &mut dyn FnMut(ShellChunk),
+        timeout: Option<Duration>,
+    ) -> Result<ExecResult> {
+        self.auto_sync_if_needed().await?;
+        let command_vec = vec!["sh".to_string(), "-c".to_string(), command.to_string()];
+        let opts = dagger_sdk::ContainerWithExecOptsBuilder::default()
+            .expect(dagger_sdk::ReturnType::Any)
+            .build()
+            .unwrap();
+        let ctr = self.ctr.clone().with_exec_opts(command_vec, opts);
+
+        let fetch = async {
+            let exit_code = ctr.exit_code().await?;
+            let stdout = ctr.stdout().await?;
+            let stderr = ctr.stderr().await?;
+            Ok::<_, eyre::Report>((exit_code, stdout, stderr))
+        };
+
+        let (exit_code, stdout, stderr) = match timeout {
+            Some(duration) => tokio::time::timeout(duration, fetch)
+                .await
+                .map_err(|_| eyre::eyre!("command timed out after {duration:?}: {command}"))??,
+            None => fetch.await?,
+        };
+
+        for line in stdout.lines() {
+            on_chunk(ShellChunk::Stdout(line.as_bytes().to_vec()));
+        }
+        for line in stderr.lines() {
+            on_chunk(ShellChunk::Stderr(line.as_bytes().to_vec()));
+        }
+        on_chunk(ShellChunk::Exited(exit_code as isize));
+
+        self.ctr = ctr;
+        self.ops_since_sync += 1;
+
+        Ok(ExecResult {
+            exit_code: exit_code as isize,
+            stdout,
+            stderr,
         })
     }
 }
@@ -0,0 +1,115 @@
+pub mod checkpoint;
+pub mod dagger;
+pub mod remote;
+pub mod shell;
+
+pub use checkpoint::{CheckpointId, CheckpointStore};
+pub use dagger::Sandbox as DaggerSandbox;
+pub use remote::Sandbox as RemoteSandbox;
+pub use shell::{ShellChunk, ShellSession};
+
+use eyre::Result;
+use std::time::Duration;
+
+/// result of running a shell command in a sandbox
+#[derive(Debug, Clone)]
+pub struct ExecResult {
+    pub exit_code: isize,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// a place to run shell commands and manipulate files, backed by a Dagger container
+/// ([`DaggerSandbox`]), a remote host ([`RemoteSandbox`]) or the local filesystem
+/// (`edda_forge::local::LocalSandbox`)
+pub trait Sandbox {
+    async fn exec(&mut self, command: &str) -> Result<ExecResult>;
+    async fn write_file(&mut self, path: &str, content: &str) -> Result<()>;
+    async fn write_files(&mut self, files: Vec<(&str, &str)>) -> Result<()>;
+    async fn read_file(&self, path: &str) -> Result<String>;
+    async fn delete_file(&mut self, path: &str) -> Result<()>;
+    async fn list_directory(&self, path: &str) -> Result<Vec<String>>;
+    async fn set_workdir(&mut self, path: &str) -> Result<()>;
+    async fn export_directory(&self, container_path: &str, host_path: &str) -> Result<String>;
+
+    /// re-sync a container path from a host path; not every runtime supports this
+    async fn refresh_from_host(&mut self, host_path: &str, container_path: &str) -> Result<()> {
+        let _ = (host_path, container_path);
+        Err(eyre::eyre!("refresh_from_host is not supported by this sandbox"))
+    }
+
+    /// bundle a container path into a single gzip-compressed tar at `host_path`, returning the
+    /// final archive path. `compression_level` trades speed for size (0 = fastest/largest,
+    /// 9 = slowest/smallest); `None` uses the default level. Not every runtime supports this.
+    async fn export_archive(
+        &self,
+        container_path: &str,
+        host_path: &str,
+        compression_level: Option<u32>,
+    ) -> Result<String> {
+        let _ = (container_path, host_path, compression_level);
+        Err(eyre::eyre!("export_archive is not supported by this sandbox"))
+    }
+
+    /// set or overwrite an environment variable for subsequent `exec`/`exec_streaming` calls,
+    /// without rebuilding the sandbox from scratch; not every runtime supports this
+    async fn set_env(&mut self, key: &str, value: &str) -> Result<()> {
+        let _ = (key, value);
+        Err(eyre::eyre!("set_env is not supported by this sandbox"))
+    }
+
+    /// clone the sandbox so two branches can diverge from the same state; not every runtime
+    /// supports this
+    async fn fork(&self) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(eyre::eyre!("fork is not supported by this sandbox"))
+    }
+
+    /// whether a file written through a [`Sandbox::fork`]ed clone becomes visible through the
+    /// original (and vice versa) once both are done — i.e. whether concurrent steps run on forks
+    /// can stand in for steps run on one shared filesystem. Defaults to `true`, since a backend
+    /// that can't support this should instead make `fork` itself fail: the only built-in backend
+    /// that overrides `fork` without sharing a filesystem is Dagger, whose forks are independent,
+    /// content-addressed container branches.
+    fn fork_shares_filesystem(&self) -> bool {
+        true
+    }
+
+    /// snapshot the sandbox's current state under `label`, returning an id that can later be
+    /// passed to [`Sandbox::restore`]. Not every runtime supports this.
+    async fn checkpoint(&mut self, label: &str) -> Result<CheckpointId> {
+        let _ = label;
+        Err(eyre::eyre!("checkpoint is not supported by this sandbox"))
+    }
+
+    /// roll back to a previously recorded checkpoint, discarding any state mutated since
+    async fn restore(&mut self, id: &CheckpointId) -> Result<()> {
+        let _ = id;
+        Err(eyre::eyre!("restore is not supported by this sandbox"))
+    }
+
+    /// open a long-lived, attached process: write stdin incrementally and read stdout/stderr
+    /// chunks as they arrive, instead of buffering a single `ExecResult`. Not every runtime
+    /// supports this.
+    async fn shell(&mut self, program: &str) -> Result<ShellSession> {
+        let _ = program;
+        Err(eyre::eyre!("shell is not supported by this sandbox"))
+    }
+
+    /// run `command`, invoking `on_chunk` with stdout/stderr as they become available rather
+    /// than only after the command finishes. If `timeout` elapses first, the command is
+    /// cancelled and an error is returned instead of hanging forever. Not every runtime
+    /// supports this; silently falling back to `exec` would drop the caller's timeout, so
+    /// implementations that can't honor it must override this rather than rely on the default.
+    async fn exec_streaming(
+        &mut self,
+        command: &str,
+        on_chunk: &mut dyn FnMut(ShellChunk),
+        timeout: Option<Duration>,
+    ) -> Result<ExecResult> {
+        let _ = (on_chunk, timeout);
+        Err(eyre::eyre!("exec_streaming is not supported by this sandbox"))
+    }
+}
@@ -0,0 +1,52 @@
+use eyre::Result;
+use tokio::sync::mpsc;
+
+/// one piece of output (or the final exit) from an interactive [`ShellSession`]
+#[derive(Debug, Clone)]
+pub enum ShellChunk {
+    Stdout(Vec<u8>),
+    Stderr(Vec<u8>),
+    /// the session's program has exited; no further chunks will follow
+    Exited(isize),
+}
+
+/// a long-lived, attached process inside a sandbox, opened via [`crate::Sandbox::shell`].
+///
+/// Unlike `exec`, which buffers a whole `ExecResult`, a session lets a human or agent send
+/// follow-up stdin and observe output incrementally via [`ShellSession::next_chunk`].
+pub struct ShellSession {
+    stdin_tx: Option<mpsc::UnboundedSender<Vec<u8>>>,
+    chunks_rx: mpsc::UnboundedReceiver<ShellChunk>,
+}
+
+impl ShellSession {
+    pub(crate) fn new(
+        stdin_tx: mpsc::UnboundedSender<Vec<u8>>,
+        chunks_rx: mpsc::UnboundedReceiver<ShellChunk>,
+    ) -> Self {
+        Self { stdin_tx: Some(stdin_tx), chunks_rx }
+    }
+
+    /// queue bytes to be sent to the session's stdin
+    pub fn write_stdin(&self, data: &[u8]) -> Result<()> {
+        self.stdin_tx
+            .as_ref()
+            .ok_or_else(|| eyre::eyre!("shell session stdin closed"))?
+            .send(data.to_vec())
+            .map_err(|_| eyre::eyre!("shell session stdin closed"))
+    }
+
+    /// signal EOF on stdin: no further `write_stdin` calls will be accepted. A live pty backend
+    /// (e.g. [`crate::RemoteSandbox`]) forwards this as a real EOF to the running process; a
+    /// request/response backend (e.g. [`crate::DaggerSandbox`]) instead uses it as the signal to
+    /// stop buffering and actually run the command with whatever stdin was written so far.
+    /// Also happens implicitly when the session is dropped.
+    pub fn close_stdin(&mut self) {
+        self.stdin_tx = None;
+    }
+
+    /// receive the next chunk of output, or `None` once the session has closed
+    pub async fn next_chunk(&mut self) -> Option<ShellChunk> {
+        self.chunks_rx.recv().await
+    }
+}